@@ -0,0 +1,77 @@
+use crate::model::{AgeGroup, Meet, Swimmer, SwimmerTime};
+use chrono::{Datelike, NaiveDate};
+
+/// Age groups used while the tenant has not set its own, as (name, min age, max age).
+const DEFAULT_AGE_GROUPS: [(&str, Option<i32>, Option<i32>); 5] = [
+    ("10 & Under", None, Some(10)),
+    ("11-12", Some(11), Some(12)),
+    ("13-14", Some(13), Some(14)),
+    ("15-17", Some(15), Some(17)),
+    ("18 & Over", Some(18), None),
+];
+
+const DEFAULT_AGE_RULE: &str = "MEET_START";
+
+/// How a tenant determines the age of swimmers and the age groups they compete in.
+pub struct AgeRules {
+    pub rule: String,
+    pub groups: Vec<AgeGroup>,
+    /// Whether the groups are the defaults, not set by the tenant.
+    pub default_groups: bool,
+}
+
+impl AgeRules {
+    pub fn new(rule: Option<String>, groups: Vec<AgeGroup>) -> Self {
+        let default_groups = groups.is_empty();
+        let groups = match default_groups {
+            true => DEFAULT_AGE_GROUPS
+                .iter()
+                .map(|(name, min_age, max_age)| AgeGroup {
+                    id: 0,
+                    name: name.to_string(),
+                    min_age: *min_age,
+                    max_age: *max_age,
+                })
+                .collect(),
+            false => groups,
+        };
+
+        Self {
+            rule: rule.unwrap_or_else(|| DEFAULT_AGE_RULE.to_string()),
+            groups,
+            default_groups,
+        }
+    }
+
+    /// Age of the swimmer competing on the date, when the date of birth is known.
+    pub fn age_on(&self, swimmer: &Swimmer, date: NaiveDate) -> Option<i32> {
+        match self.rule.as_str() {
+            "DECEMBER_31" => swimmer.age_on(NaiveDate::from_ymd_opt(date.year(), 12, 31)?),
+            _ => swimmer.age_on(date),
+        }
+    }
+
+    pub fn age_at(&self, swimmer: &Swimmer, meet: &Meet) -> Option<i32> {
+        self.age_on(swimmer, meet.start_date)
+    }
+
+    pub fn age_group(&self, age: Option<i32>) -> Option<&AgeGroup> {
+        age.and_then(|age| self.groups.iter().find(|group| group.contains(age)))
+    }
+
+    /// Sets the age and age group of the swimmers at the meets of the times. Times without the
+    /// dates of their meets use the date they were swum.
+    pub fn assign_ages(&self, times: &mut [SwimmerTime]) {
+        for swimmer_time in times {
+            let date = match swimmer_time.meet.start_date {
+                NaiveDate::MIN => swimmer_time.time_date,
+                start_date => start_date,
+            };
+            swimmer_time.age = self.age_on(&swimmer_time.swimmer, date);
+            swimmer_time.age_group = self
+                .age_group(swimmer_time.age)
+                .map(|group| group.name.clone())
+                .unwrap_or_default();
+        }
+    }
+}
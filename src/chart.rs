@@ -0,0 +1,333 @@
+use crate::model::{SwimmerTime, TimeStandard};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt::Write;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 60.0;
+/// Room for the names of the standards, written at the end of their lines.
+const MARGIN_RIGHT: f64 = 120.0;
+const MARGIN_TOP: f64 = 15.0;
+const MARGIN_BOTTOM: f64 = 30.0;
+
+/// Standards much faster or slower than the swimmer would flatten the chart, so only standards
+/// within this fraction of the swimmer's times are drawn.
+const STANDARD_RANGE: f64 = 0.1;
+
+#[derive(Serialize)]
+pub struct EventChart {
+    pub style: String,
+    pub distance: i32,
+    pub course: String,
+    pub svg: String,
+}
+
+struct Point<'a> {
+    date: NaiveDate,
+    time: i32,
+    personal_best: bool,
+    swimmer_time: &'a SwimmerTime,
+}
+
+/// Formats milliseconds as results do, like 1:05.32 or 28.71.
+pub fn format_time(time: i32) -> String {
+    let minutes = time / 60000;
+    let seconds = (time / 1000) % 60;
+    let hundredths = (time / 10) % 100;
+    if minutes > 0 {
+        format!("{}:{:02}.{:02}", minutes, seconds, hundredths)
+    } else {
+        format!("{}.{:02}", seconds, hundredths)
+    }
+}
+
+/// Draws a chart for each event and course the swimmer swam, with the standards of the event
+/// that apply to the swimmer's gender and age.
+pub fn event_charts(
+    times: &[SwimmerTime],
+    standards: &[TimeStandard],
+    age: Option<i32>,
+) -> Vec<EventChart> {
+    let mut events: Vec<(&str, &str, i32)> = times
+        .iter()
+        .map(|st| (st.course.as_str(), st.style.as_str(), st.distance))
+        .collect();
+    events.sort();
+    events.dedup();
+
+    events
+        .into_iter()
+        .map(|(course, style, distance)| {
+            let event_times: Vec<&SwimmerTime> = times
+                .iter()
+                .filter(|st| st.course == course && st.style == style && st.distance == distance)
+                .collect();
+            let gender = &event_times[0].swimmer.gender;
+            let event_standards: Vec<&TimeStandard> = standards
+                .iter()
+                .filter(|ts| ts.course == course && ts.style == style && ts.distance == distance)
+                .filter(|ts| &ts.gender == gender && ts.applies_to(age))
+                .collect();
+            EventChart {
+                style: style.to_string(),
+                distance,
+                course: course.to_string(),
+                svg: progression_chart(&event_times, &event_standards),
+            }
+        })
+        .collect()
+}
+
+/// Draws the times of one event against their dates, faster times higher. Personal bests are
+/// green and every point tells its meet on hover. Standards are dashed lines.
+pub fn progression_chart(times: &[&SwimmerTime], standards: &[&TimeStandard]) -> String {
+    let points = to_points(times);
+    if points.is_empty() {
+        return String::new();
+    }
+
+    let first_date = points.iter().map(|p| p.date).min().unwrap();
+    let last_date = points.iter().map(|p| p.date).max().unwrap();
+    let fastest = points.iter().map(|p| p.time).min().unwrap();
+    let slowest = points.iter().map(|p| p.time).max().unwrap();
+
+    let standards: Vec<&&TimeStandard> = standards
+        .iter()
+        .filter(|ts| {
+            let time = ts.time as f64;
+            time >= fastest as f64 * (1.0 - STANDARD_RANGE)
+                && time <= slowest as f64 * (1.0 + STANDARD_RANGE)
+        })
+        .collect();
+
+    let low = standards
+        .iter()
+        .map(|ts| ts.time)
+        .chain([fastest])
+        .min()
+        .unwrap();
+    let high = standards
+        .iter()
+        .map(|ts| ts.time)
+        .chain([slowest])
+        .max()
+        .unwrap();
+    let scale = Scale::new(first_date, last_date, low, high);
+    let mut svg = open_svg(&scale, fastest, slowest);
+    let right = WIDTH - MARGIN_RIGHT;
+
+    for standard in standards {
+        let _ = write!(
+            svg,
+            r##"<line x1="{MARGIN_LEFT}" y1="{0:.1}" x2="{right}" y2="{0:.1}" stroke="#dc3545" stroke-dasharray="4 3"><title>{1} {2}</title></line><text x="{3:.1}" y="{0:.1}" dominant-baseline="middle" fill="#dc3545">{1}</text>"##,
+            scale.y(standard.time),
+            escape(&standard.name()),
+            format_time(standard.time),
+            right + 5.0,
+        );
+    }
+
+    let line: Vec<String> = points
+        .iter()
+        .map(|p| format!("{:.1},{:.1}", scale.x(p.date), scale.y(p.time)))
+        .collect();
+    let _ = write!(
+        svg,
+        r##"<polyline points="{}" fill="none" stroke="#0d6efd" stroke-width="1.5"/>"##,
+        line.join(" ")
+    );
+
+    for point in &points {
+        let source = match point.swimmer_time.dataset.as_str() {
+            "MEET_ENTRIES" => format!("Entry time for {}", point.swimmer_time.meet.name),
+            _ => point.swimmer_time.meet.name.clone(),
+        };
+        let _ = write!(
+            svg,
+            r##"<circle cx="{:.1}" cy="{:.1}" r="4" fill="{}"><title>{} | {} | {}{}</title></circle>"##,
+            scale.x(point.date),
+            scale.y(point.time),
+            if point.personal_best {
+                "#198754"
+            } else {
+                "#6c757d"
+            },
+            escape(&source),
+            point.date.format("%B %e, %Y"),
+            format_time(point.time),
+            if point.personal_best { " | PB" } else { "" },
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Places dates from left to right and times from top to bottom, so faster times are higher.
+struct Scale {
+    first_date: NaiveDate,
+    last_date: NaiveDate,
+    low: i32,
+    high: i32,
+}
+
+impl Scale {
+    /// Pads the times, so the fastest and slowest points don't touch the edges of the plot.
+    fn new(first_date: NaiveDate, last_date: NaiveDate, fastest: i32, slowest: i32) -> Self {
+        let padding = ((slowest - fastest) / 10).max(100);
+        Self {
+            first_date,
+            last_date,
+            low: fastest - padding,
+            high: slowest + padding,
+        }
+    }
+
+    fn x(&self, date: NaiveDate) -> f64 {
+        let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        if self.first_date == self.last_date {
+            MARGIN_LEFT + plot_width / 2.0
+        } else {
+            let days = (self.last_date - self.first_date).num_days() as f64;
+            MARGIN_LEFT + (date - self.first_date).num_days() as f64 / days * plot_width
+        }
+    }
+
+    fn y(&self, time: i32) -> f64 {
+        let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        MARGIN_TOP + (time - self.low) as f64 / (self.high - self.low) as f64 * plot_height
+    }
+}
+
+/// Starts the chart with its axes, labelled with the fastest and slowest times and the first and
+/// last dates.
+fn open_svg(scale: &Scale, fastest: i32, slowest: i32) -> String {
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {HEIGHT}" style="width: 100%; max-width: {WIDTH}px" role="img" font-family="sans-serif" font-size="10">"##
+    );
+
+    let bottom = HEIGHT - MARGIN_BOTTOM;
+    let right = WIDTH - MARGIN_RIGHT;
+    let _ = write!(
+        svg,
+        r##"<line x1="{MARGIN_LEFT}" y1="{MARGIN_TOP}" x2="{MARGIN_LEFT}" y2="{bottom}" stroke="#adb5bd"/><line x1="{MARGIN_LEFT}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="#adb5bd"/>"##
+    );
+    for time in [fastest, slowest] {
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"##,
+            MARGIN_LEFT - 5.0,
+            scale.y(time),
+            format_time(time)
+        );
+    }
+    let single_date = scale.first_date == scale.last_date;
+    for (date, anchor) in [(scale.first_date, "start"), (scale.last_date, "end")] {
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" text-anchor="{}">{}</text>"##,
+            scale.x(date),
+            bottom + 15.0,
+            if single_date { "middle" } else { anchor },
+            date.format("%b %Y")
+        );
+        if single_date {
+            break;
+        }
+    }
+    svg
+}
+
+/// Entry files repeat times also imported from results, so the same swim is drawn once, as a
+/// result when possible. Times equal to the best so far count as personal bests.
+fn to_points<'a>(times: &[&'a SwimmerTime]) -> Vec<Point<'a>> {
+    let mut times: Vec<&SwimmerTime> = times.to_vec();
+    times.sort_by_key(|st| (st.time_date, st.time, st.dataset != "MEET_RESULTS"));
+    times.dedup_by_key(|st| (st.time_date, st.time));
+
+    let mut best = i32::MAX;
+    times
+        .into_iter()
+        .map(|st| {
+            let personal_best = st.time <= best;
+            best = best.min(st.time);
+            Point {
+                date: st.time_date,
+                time: st.time,
+                personal_best,
+                swimmer_time: st,
+            }
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Colours of the swimmers compared in one chart.
+const SERIES_COLORS: [&str; 3] = ["#0d6efd", "#fd7e14", "#6f42c1"];
+
+/// Draws the progression of several swimmers in the same event, one line each, named at the end
+/// of the chart.
+pub fn comparison_chart(series: &[Vec<&SwimmerTime>]) -> String {
+    let series: Vec<Vec<Point>> = series
+        .iter()
+        .map(|times| to_points(times))
+        .filter(|points| !points.is_empty())
+        .collect();
+    let all_points = || series.iter().flatten();
+    if series.is_empty() {
+        return String::new();
+    }
+
+    let first_date = all_points().map(|p| p.date).min().unwrap();
+    let last_date = all_points().map(|p| p.date).max().unwrap();
+    let fastest = all_points().map(|p| p.time).min().unwrap();
+    let slowest = all_points().map(|p| p.time).max().unwrap();
+    let scale = Scale::new(first_date, last_date, fastest, slowest);
+    let mut svg = open_svg(&scale, fastest, slowest);
+    let right = WIDTH - MARGIN_RIGHT;
+
+    for (i, points) in series.iter().enumerate() {
+        let color = SERIES_COLORS[i % SERIES_COLORS.len()];
+        let swimmer = &points[0].swimmer_time.swimmer;
+        let name = escape(&format!("{} {}", swimmer.first_name, swimmer.last_name));
+
+        let line: Vec<String> = points
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", scale.x(p.date), scale.y(p.time)))
+            .collect();
+        let _ = write!(
+            svg,
+            r##"<polyline points="{}" fill="none" stroke="{color}" stroke-width="1.5"/>"##,
+            line.join(" ")
+        );
+        for point in points {
+            let _ = write!(
+                svg,
+                r##"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{color}"><title>{name} | {} | {} | {}</title></circle>"##,
+                scale.x(point.date),
+                scale.y(point.time),
+                escape(&point.swimmer_time.meet.name),
+                point.date.format("%B %e, %Y"),
+                format_time(point.time),
+            );
+        }
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" fill="{color}">{name}</text>"##,
+            right + 5.0,
+            MARGIN_TOP + 12.0 * i as f64 + 5.0,
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
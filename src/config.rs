@@ -6,6 +6,14 @@ use config::ConfigError;
 pub struct Config {
     pub database: DatabaseConfig,
     pub server_port: u16,
+    #[serde(default)]
+    pub club: String,
+    /// Addresses of the reverse proxies trusted to tell the original host of requests.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+    /// Secret of at least 32 bytes encrypting the coach selected in browsers.
+    #[serde(default)]
+    pub cookie_key: String,
 }
 
 #[derive(serde::Deserialize)]
@@ -33,10 +41,22 @@ fn load_config_from_env(e: ConfigError) -> Result<Config, config::ConfigError> {
         .parse()
         .expect("PORT must be a number");
 
+    let club = env::var("CLUB").unwrap_or_default();
+    let cookie_key = env::var("COOKIE_KEY").unwrap_or_default();
+    let trusted_proxies: Vec<String> = env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .map(|proxy| proxy.trim().to_string())
+        .filter(|proxy| !proxy.is_empty())
+        .collect();
+
     let config: Config = match env::var("DATABASE_URL") {
         Ok(url) => Config {
             server_port: port,
             database: DatabaseConfig { url },
+            club,
+            trusted_proxies,
+            cookie_key,
         },
         Err(e) => {
             log::error!("DATABASE_URL: {}", e);
@@ -45,6 +65,9 @@ fn load_config_from_env(e: ConfigError) -> Result<Config, config::ConfigError> {
                 database: DatabaseConfig {
                     url: String::from(""),
                 },
+                club,
+                trusted_proxies,
+                cookie_key,
             }
         }
     };
@@ -1,14 +1,490 @@
-use crate::model::AppState;
-use crate::repository::{find_latest_imported_swimmers, find_meet, find_meet_swimmers, find_meets_with_results};
-use actix_web::{web, HttpResponse, Responder};
-use serde::Deserialize;
+use crate::age::AgeRules;
+use crate::chart::{comparison_chart, event_charts, progression_chart};
+use crate::conversion::CourseConversions;
+use crate::dashboard::{group_metrics, group_swims, Period};
+use crate::forecast::forecast_all;
+use crate::matching::{resolve, split_full_name, NameQuery, Resolution};
+use crate::model::{
+    convert_gender, Actor, AgeGroup, AppState, AuditEntry, ClubRecord, ConvertedTime,
+    CourseConversion, ImportHistory, Meet, MeetEvent, NameReview, Season, StandardSet, Swimmer,
+    SwimmerAlias, SwimmerTime, Tenant, TimeStandard, AGE_RULES, COURSES, MEET_TYPES,
+};
+use crate::points::{BaseTimeTable, Points, PointsTable, ScoreTable, WorldAquatics};
+use crate::qualification::{find_qualifications, Qualification};
+use crate::rankings::rank;
+use crate::records::{current_records, detect_records, OPEN_AGE_GROUP};
+use crate::repository::{
+    activate_points_table, assign_group_coach, delete_age_group, delete_club_record,
+    delete_course_conversion, delete_meet, delete_meet_club_records, delete_meet_session,
+    delete_points_table, delete_season, delete_standard_set, delete_swimmer_alias,
+    end_group_member, find_age_groups, find_age_rule, find_all_clubs, find_all_coaches,
+    find_all_meets, find_all_seasons, find_all_standard_sets, find_all_swimmer_aliases,
+    find_all_swimmers, find_all_training_groups, find_audit_entries, find_best_times,
+    find_club_records, find_club_results, find_club_swims, find_coach, find_comparison_times,
+    find_course_conversions, find_followed_meet, find_group_members, find_import_history,
+    find_latest_imported_swimmers, find_meet, find_meet_dependents, find_meet_sessions,
+    find_meet_swimmers, find_meets_with_results, find_name_review, find_optional_meet,
+    find_pending_name_reviews, find_points_rows, find_points_tables, find_standard_set,
+    find_swimmer, find_swimmer_aliases, find_swimmer_merges, find_swimmer_times,
+    find_swimmers_best_times, find_swimmers_history, find_time_standards, find_training_group,
+    follow_meet, ignore_name_review, insert_age_group, insert_audit, insert_club_membership,
+    insert_club_records, insert_coach, insert_group_member, insert_meet, insert_meet_event,
+    insert_meet_session, insert_season, insert_standard_set, insert_swimmer, insert_swimmer_alias,
+    insert_training_group, merge_swimmers, resolve_name_review, rollback_import, save_age_rule,
+    save_club, save_course_conversion, unassign_group_coach, update_meet,
+    update_standard_set_tracking, update_swimmer,
+};
+use actix_web::cookie::{Cookie, CookieJar};
+use actix_web::dev::Payload;
+use actix_web::error::{ErrorInternalServerError, ErrorNotFound};
+use actix_web::http::header::{HOST, LOCATION};
+use actix_web::web::Redirect;
+use actix_web::{web, Either, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
 use tera::Context;
 
+/// Cookie keeping the coach selected in the browser, to know who changes what.
+const ACTOR_COOKIE: &str = "coach";
+
+impl FromRequest for Tenant {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let state = match req.app_data::<web::Data<AppState>>() {
+            Some(state) => state,
+            None => return ready(Err(ErrorInternalServerError("No application state"))),
+        };
+        let host = request_host(req, state);
+
+        ready(
+            state
+                .find_tenant(&host)
+                .cloned()
+                .ok_or_else(|| ErrorNotFound(format!("No tenant for host {}", host))),
+        )
+    }
+}
+
+/// Whether the request comes from a reverse proxy of the configuration, whose forwarded headers
+/// can be believed.
+fn from_trusted_proxy(req: &HttpRequest, state: &AppState) -> bool {
+    req.peer_addr().is_some_and(|peer| {
+        state
+            .trusted_proxies
+            .iter()
+            .any(|proxy| *proxy == peer.ip().to_string())
+    })
+}
+
+/// The host the client asked for. Forwarded hosts are only taken from trusted proxies, since any
+/// client can send them.
+fn request_host(req: &HttpRequest, state: &AppState) -> String {
+    let host = if from_trusted_proxy(req, state) {
+        req.connection_info().host().to_string()
+    } else {
+        req.headers()
+            .get(HOST)
+            .and_then(|host| host.to_str().ok())
+            .or_else(|| req.uri().host())
+            .unwrap_or_default()
+            .to_string()
+    };
+    host.split(':').next().unwrap_or_default().to_lowercase()
+}
+
+/// The selected coach, only when the cookie was encrypted with the key of the app.
+fn open_actor_cookie(req: &HttpRequest, state: &AppState) -> Option<String> {
+    let mut jar = CookieJar::new();
+    jar.add_original(req.cookie(ACTOR_COOKIE)?);
+    jar.private(&state.cookie_key)
+        .get(ACTOR_COOKIE)
+        .map(|cookie| cookie.value().to_string())
+}
+
+impl FromRequest for Actor {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let tenant = Tenant::from_request(req, payload);
+        let state = req.app_data::<web::Data<AppState>>().cloned();
+        let coach_id = state
+            .as_ref()
+            .and_then(|state| open_actor_cookie(req, state))
+            .and_then(|value| value.parse::<i32>().ok());
+        // Any client can send the user and forwarded addresses, so only trusted proxies tell them.
+        let trusted = state
+            .as_ref()
+            .is_some_and(|state| from_trusted_proxy(req, state));
+        let remote_user = req
+            .headers()
+            .get("Remote-User")
+            .filter(|_| trusted)
+            .and_then(|user| user.to_str().ok())
+            .map(|user| user.trim().to_string())
+            .filter(|user| !user.is_empty());
+        let address = if trusted {
+            req.connection_info().realip_remote_addr().map(String::from)
+        } else {
+            req.peer_addr().map(|peer| peer.ip().to_string())
+        };
+        let address = format!("{} (unverified)", address.as_deref().unwrap_or("unknown"));
+
+        Box::pin(async move {
+            let tenant = tenant.await?;
+            if let (Some(state), Some(coach_id)) = (state, coach_id) {
+                if let Some(coach) = find_coach(&state.pool, &tenant.id, coach_id).await {
+                    return Ok(Actor {
+                        coach: Some(coach.id),
+                        name: coach.name,
+                    });
+                }
+            }
+            Ok(Actor {
+                coach: None,
+                name: remote_user.unwrap_or(address),
+            })
+        })
+    }
+}
+
 #[derive(Deserialize)]
 pub struct MeetPath {
     pub id: String,
 }
 
+#[derive(Deserialize)]
+pub struct MeetForm {
+    pub id: String,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub course: String,
+    pub location: String,
+    pub pool: String,
+    pub host_club: String,
+    pub sanction: String,
+    pub meet_type: String,
+}
+
+impl MeetForm {
+    fn into_meet(self) -> Meet {
+        Meet {
+            id: self.id.trim().to_string(),
+            name: self.name.trim().to_string(),
+            start_date: self.start_date,
+            end_date: self.end_date,
+            course: self.course,
+            location: self.location.trim().to_string(),
+            pool: self.pool.trim().to_string(),
+            host_club: self.host_club.trim().to_uppercase(),
+            sanction: self.sanction.trim().to_string(),
+            meet_type: self.meet_type,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MeetSessionPath {
+    pub id: String,
+    pub session: i32,
+}
+
+#[derive(Deserialize)]
+pub struct MeetSessionForm {
+    pub session_date: NaiveDate,
+    pub period: String,
+}
+
+#[derive(Deserialize)]
+pub struct MeetEventForm {
+    pub gender: String,
+    pub style: String,
+    pub distance: i32,
+    pub round: String,
+}
+
+#[derive(Deserialize)]
+pub struct SwimmerPath {
+    pub id: String,
+}
+
+#[derive(Deserialize)]
+pub struct SwimmerForm {
+    pub first_name: String,
+    pub last_name: String,
+    pub gender: String,
+    pub birth_date: String,
+    pub active: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SwimmerAliasPath {
+    pub id: String,
+    pub alias: i32,
+}
+
+#[derive(Deserialize)]
+pub struct SwimmerAliasForm {
+    pub alias_id: String,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct SwimmerMergeForm {
+    pub survivor: String,
+    pub merged: String,
+}
+
+#[derive(Deserialize)]
+pub struct NameReviewPath {
+    pub review: i32,
+}
+
+#[derive(Deserialize)]
+pub struct NameReviewLinkForm {
+    pub swimmer: String,
+}
+
+#[derive(Deserialize)]
+pub struct NameReviewCreateForm {
+    pub id: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub gender: String,
+    pub birth_date: String,
+}
+
+#[derive(Deserialize)]
+pub struct MeetQuery {
+    pub round: Option<String>,
+    pub club: Option<String>,
+    pub group: Option<String>,
+    /// Best times before the meet when missing, the entry times of the meet with `entries`, or
+    /// the results of another meet by its id.
+    #[serde(rename = "compare-to")]
+    pub compare: Option<String>,
+    /// Name of the age group of the swimmers at the meet, or all when missing or empty.
+    #[serde(rename = "age-group")]
+    pub age_group: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SeasonQuery {
+    pub season: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SwimmerQuery {
+    pub season: Option<String>,
+    pub target: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+pub struct SeasonPath {
+    pub season: i32,
+}
+
+#[derive(Deserialize)]
+pub struct SeasonForm {
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub long_course_start: String,
+}
+
+#[derive(Deserialize)]
+pub struct GroupPath {
+    pub group: i32,
+}
+
+#[derive(Deserialize)]
+pub struct GroupMemberPath {
+    pub group: i32,
+    pub member: i32,
+}
+
+#[derive(Deserialize)]
+pub struct GroupCoachPath {
+    pub group: i32,
+    pub coach: i32,
+}
+
+#[derive(Deserialize)]
+pub struct GroupForm {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct CoachForm {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct GroupMemberForm {
+    pub swimmer: String,
+    pub start_date: NaiveDate,
+}
+
+#[derive(Deserialize)]
+pub struct GroupCoachForm {
+    pub coach: i32,
+}
+
+#[derive(Deserialize)]
+pub struct CoachActForm {
+    pub coach: String,
+}
+
+#[derive(Deserialize)]
+pub struct ConversionForm {
+    pub gender: String,
+    pub style: String,
+    pub from_course: String,
+    pub from_distance: i32,
+    pub to_course: String,
+    #[serde(default)]
+    pub factor: f64,
+}
+
+#[derive(Deserialize)]
+pub struct StandardSetPath {
+    pub set: i32,
+}
+
+#[derive(Deserialize)]
+pub struct StandardSetForm {
+    pub name: String,
+    pub qualifying_start: String,
+    pub qualifying_end: String,
+}
+
+#[derive(Deserialize)]
+pub struct StandardSetQuery {
+    /// Rows of the last loaded file that could not be read.
+    pub skipped: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct PointsTablePath {
+    pub table: i32,
+}
+
+#[derive(Deserialize)]
+pub struct PointsActivateForm {
+    /// Empty for World Aquatics points.
+    pub table: String,
+}
+
+#[derive(Deserialize)]
+pub struct PointsQuery {
+    /// Rows of the last loaded file that could not be read.
+    pub skipped: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct AgeRuleForm {
+    pub rule: String,
+}
+
+#[derive(Deserialize)]
+pub struct AgeGroupPath {
+    pub age_group: i32,
+}
+
+#[derive(Deserialize)]
+pub struct AgeGroupForm {
+    pub name: String,
+    pub min_age: String,
+    pub max_age: String,
+}
+
+#[derive(Deserialize)]
+pub struct GapQuery {
+    pub club: Option<String>,
+    pub group: Option<String>,
+    /// All courses when missing or empty.
+    pub course: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct QualificationQuery {
+    pub club: Option<String>,
+    pub group: Option<String>,
+    /// Every tracked set when missing or empty.
+    pub set: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RecordQuery {
+    pub group: Option<String>,
+    pub gender: Option<String>,
+    pub course: Option<String>,
+    #[serde(rename = "age-group")]
+    pub age_group: Option<String>,
+    /// Rows of the last loaded file that could not be read.
+    pub skipped: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct RecordPath {
+    pub record: i32,
+}
+
+#[derive(Deserialize)]
+pub struct RankingQuery {
+    pub season: Option<String>,
+    pub group: Option<String>,
+    pub gender: Option<String>,
+    pub course: Option<String>,
+    #[serde(rename = "age-group")]
+    pub age_group: Option<String>,
+    pub top: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct AuditQuery {
+    pub meet: Option<String>,
+    pub swimmer: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ClubForm {
+    pub code: String,
+    pub name: String,
+}
+
+/// Without a filter, the current season is selected, while an empty filter selects all time.
+pub fn select_season(seasons: &[Season], season: &Option<String>) -> Option<Season> {
+    match season.as_deref() {
+        None => {
+            let today = Local::now().date_naive();
+            seasons
+                .iter()
+                .find(|s| s.start_date <= today && today <= s.end_date)
+                .cloned()
+        }
+        Some(id) => id
+            .parse::<i32>()
+            .ok()
+            .and_then(|id| seasons.iter().find(|s| s.id == id).cloned()),
+    }
+}
+
+/// Group filters are optional, and their select sends an empty value for all groups.
+pub fn parse_group(group: &Option<String>) -> Option<i32> {
+    group.as_deref().and_then(|g| g.parse().ok())
+}
+
 pub async fn home_view(state: web::Data<AppState>) -> impl Responder {
     let context = Context::new();
 
@@ -23,11 +499,18 @@ pub async fn home_view(state: web::Data<AppState>) -> impl Responder {
         )
 }
 
-pub async fn meet_view(path: web::Path<MeetPath>, state: web::Data<AppState>) -> impl Responder {
+pub async fn meet_view(
+    tenant: Tenant,
+    path: web::Path<MeetPath>,
+    query: web::Query<MeetQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
     let meet = find_meet(&state.get_ref().pool, &path.id).await;
-    let meets_with_results = find_meets_with_results(&state.get_ref().pool, &meet.id).await;
+    let meets_with_results =
+        find_meets_with_results(&state.get_ref().pool, &tenant.id, &meet.id).await;
 
-    let import_history = find_latest_imported_swimmers(&state.get_ref().pool, &meet.id).await;
+    let import_history =
+        find_latest_imported_swimmers(&state.get_ref().pool, &tenant.id, &meet.id).await;
     let entries_loaded = import_history
         .iter()
         .filter(|i| i.dataset == "MEET_ENTRIES")
@@ -47,29 +530,2990 @@ pub async fn meet_view(path: web::Path<MeetPath>, state: web::Data<AppState>) ->
         .iter()
         .find(|i| i.dataset == "MEET_RESULTS")
         .expect("No result swimmers");
-    let result_swimmers = find_meet_swimmers(&state.get_ref().pool, meet_results_history).await;
+    let round = query.round.clone().unwrap_or_default();
+    let club = query.club.clone().unwrap_or_else(|| tenant.club.clone());
+    let group = parse_group(&query.group);
+    let groups = find_all_training_groups(&state.get_ref().pool, &tenant.id).await;
+    let mut result_swimmers = find_meet_swimmers(
+        &state.get_ref().pool,
+        &tenant.id,
+        meet_results_history,
+        &round,
+        &club,
+        group,
+    )
+    .await;
+    let age_rules = find_age_rules(&state.get_ref().pool, &tenant).await;
+    age_rules.assign_ages(&mut result_swimmers);
+    let age_group = query.age_group.clone().unwrap_or_default();
+    if !age_group.is_empty() {
+        result_swimmers.retain(|st| st.age_group == age_group);
+    }
+    // The report is grouped by age group, from the youngest, with unknown ages last.
+    let age_group_order = |st: &SwimmerTime| {
+        age_rules
+            .groups
+            .iter()
+            .position(|g| g.name == st.age_group)
+            .unwrap_or(age_rules.groups.len())
+    };
+    result_swimmers.sort_by_key(age_group_order);
+    let points = find_points(&state.get_ref().pool, &tenant).await;
+    points.score_all(&mut result_swimmers);
+
+    // Times swum in other courses are converted to compare with the results.
+    let compare = query.compare.clone().unwrap_or_else(|| "0".to_string());
+    let swimmer_ids: Vec<String> = result_swimmers
+        .iter()
+        .map(|st| st.swimmer.id.clone())
+        .collect();
+    let pool = &state.get_ref().pool;
+    let comparison_times = match compare.as_str() {
+        "" => vec![],
+        "0" => {
+            find_comparison_times(
+                pool,
+                &tenant.id,
+                &swimmer_ids,
+                None,
+                None,
+                Some(meet.start_date),
+            )
+            .await
+        }
+        "entries" => {
+            find_comparison_times(
+                pool,
+                &tenant.id,
+                &swimmer_ids,
+                Some(&meet.id),
+                Some("MEET_ENTRIES"),
+                None,
+            )
+            .await
+        }
+        other => {
+            find_comparison_times(
+                pool,
+                &tenant.id,
+                &swimmer_ids,
+                Some(other),
+                Some("MEET_RESULTS"),
+                None,
+            )
+            .await
+        }
+    };
+    let conversions = CourseConversions::new(find_course_conversions(pool, &tenant.id).await);
+    let history = find_swimmers_history(pool, &tenant.id, &swimmer_ids, meet.end_date).await;
+    let standards = find_time_standards(pool, &tenant.id, None).await;
+
+    // Standards of the tracked sets first achieved at this meet.
+    let tracked_sets: Vec<StandardSet> = find_all_standard_sets(pool, &tenant.id)
+        .await
+        .into_iter()
+        .filter(|set| set.tracked)
+        .collect();
+    let mut results_history: Vec<SwimmerTime> = history
+        .iter()
+        .filter(|st| st.dataset == "MEET_RESULTS")
+        .cloned()
+        .collect();
+    age_rules.assign_ages(&mut results_history);
+    let new_qualifications: Vec<Qualification> =
+        find_qualifications(&tracked_sets, &standards, &results_history)
+            .into_iter()
+            .filter(|q| q.result.meet.id == meet.id)
+            .collect();
+
+    let results: Vec<ComparedResult> = result_swimmers
+        .into_iter()
+        .map(|result| {
+            let event_times: Vec<&SwimmerTime> = history
+                .iter()
+                .filter(|st| st.swimmer.id == result.swimmer.id && st.style == result.style)
+                .filter(|st| st.distance == result.distance && st.course == result.course)
+                .collect();
+            let age = result.age;
+            let event_standards: Vec<&TimeStandard> = standards
+                .iter()
+                .filter(|ts| {
+                    ts.style == result.style
+                        && ts.distance == result.distance
+                        && ts.course == result.course
+                })
+                .filter(|ts| ts.gender == result.swimmer.gender && ts.applies_to(age))
+                .collect();
+            let chart = progression_chart(&event_times, &event_standards);
+            let qualified = new_qualifications
+                .iter()
+                .filter(|q| {
+                    q.result.swimmer.id == result.swimmer.id && q.result.style == result.style
+                })
+                .filter(|q| {
+                    q.result.distance == result.distance && q.result.course == result.course
+                })
+                .filter(|q| result.time <= q.standard.time)
+                .map(|q| q.standard.name())
+                .collect();
+
+            let times = comparison_times
+                .iter()
+                .filter(|ct| ct.swimmer.id == result.swimmer.id && ct.style == result.style)
+                .cloned()
+                .collect();
+            let compared = conversions.fastest_in(times, &result.course, result.distance);
+            ComparedResult {
+                difference: compared.as_ref().map(|ct| result.time - ct.time),
+                compared,
+                result,
+                chart,
+                qualified,
+            }
+        })
+        .collect();
+
+    let new_records: Vec<ClubRecord> = find_club_records(pool, &tenant.id)
+        .await
+        .into_iter()
+        .filter(|r| r.meet == meet.id && r.source == "IMPORT")
+        .collect();
 
     let mut context = Context::new();
     context.insert("meet", &meet);
     context.insert("meets_with_results", &meets_with_results);
     context.insert("entries_loaded", &entries_loaded);
     context.insert("results_loaded", &results_loaded);
-    context.insert("result_swimmers", &result_swimmers);
+    context.insert("results", &results);
+    context.insert("compare", &compare);
+    context.insert("round", &round);
+    context.insert("club", &club);
+    context.insert("home_club", &tenant.club);
+    context.insert("group", &group);
+    context.insert("groups", &groups);
+    context.insert("points_table", points.table_name());
+    context.insert("age_group", &age_group);
+    context.insert("age_groups", &age_rules.groups);
+    context.insert("new_records", &new_records);
 
     HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(state.get_ref().template.render("meet.html", &context).unwrap())
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("meet.html", &context)
+                .unwrap(),
+        )
 }
 
-pub async fn meets_form_view(state: web::Data<AppState>) -> impl Responder {
-    let context = Context::new();
+/// A result of the meet with the time it is compared to, converted when swum in another course.
+#[derive(Serialize)]
+struct ComparedResult {
+    result: SwimmerTime,
+    compared: Option<ConvertedTime>,
+    difference: Option<i32>,
+    chart: String,
+    /// Names of the tracked standards first achieved at the meet.
+    qualified: Vec<String>,
+}
+
+pub async fn meets_form_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    render_meet_form(&state, &tenant, None, false, None).await
+}
+
+pub async fn meets_new(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<MeetForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let meet = form.into_inner().into_meet();
+    if let Some(error) = validate_meet(&meet) {
+        return Either::Right(
+            render_meet_form(&state, &tenant, Some(&meet), false, Some(error)).await,
+        );
+    }
+
+    // Meets are shared, so a meet registered by another tenant is followed as it is.
+    match find_optional_meet(&state.get_ref().pool, &meet.id).await {
+        Some(existing) => {
+            follow_meet(&state.get_ref().pool, &tenant.id, &existing.id).await;
+            if existing.name != meet.name
+                || existing.start_date != meet.start_date
+                || existing.end_date != meet.end_date
+                || existing.course != meet.course
+            {
+                let error = format!(
+                    "Meet #{} already exists as {}. Edit the meet to correct it.",
+                    existing.id, existing.name
+                );
+                return Either::Right(
+                    render_meet_form(&state, &tenant, Some(&meet), false, Some(&error)).await,
+                );
+            }
+            let mut entry = AuditEntry::new(&actor, "FOLLOW", "meet", &meet.id);
+            entry.meet = meet.id.clone();
+            entry.set_after(&existing);
+            insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+        }
+        None => {
+            if let Err(e) = insert_meet(&state.get_ref().pool, &meet).await {
+                log::error!("Failed creating meet {}: {}", meet.id, e);
+                let error = "The meet could not be saved.";
+                return Either::Right(
+                    render_meet_form(&state, &tenant, Some(&meet), false, Some(error)).await,
+                );
+            }
+            follow_meet(&state.get_ref().pool, &tenant.id, &meet.id).await;
+            let mut entry = AuditEntry::new(&actor, "CREATE", "meet", &meet.id);
+            entry.meet = meet.id.clone();
+            entry.set_after(&meet);
+            insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+        }
+    }
+
+    Either::Left(Redirect::to(format!("/meets/{}/", meet.id)).see_other())
+}
+
+pub async fn meet_edit_view(
+    tenant: Tenant,
+    path: web::Path<MeetPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    match find_followed_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(meet) => render_meet_form(&state, &tenant, Some(&meet), true, None).await,
+        None => HttpResponse::NotFound().body("Meet not found"),
+    }
+}
+
+pub async fn meet_edit(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<MeetPath>,
+    form: web::Form<MeetForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let mut meet = form.into_inner().into_meet();
+    meet.id = path.id.clone();
+
+    let existing = match find_followed_meet(&state.get_ref().pool, &tenant.id, &meet.id).await {
+        Some(existing) => existing,
+        None => return Either::Right(HttpResponse::NotFound().body("Meet not found")),
+    };
+
+    if let Some(error) = validate_meet(&meet) {
+        return Either::Right(
+            render_meet_form(&state, &tenant, Some(&meet), true, Some(error)).await,
+        );
+    }
+
+    // Results of other tenants were imported for the course and dates of the meet.
+    if (existing.course != meet.course
+        || existing.start_date != meet.start_date
+        || existing.end_date != meet.end_date)
+        && find_meet_dependents(&state.get_ref().pool, &tenant.id, &meet.id)
+            .await
+            .num_followers
+            > 0
+    {
+        let error = "Other clubs follow this meet, so its course and dates can't change.";
+        return Either::Right(
+            render_meet_form(&state, &tenant, Some(&meet), true, Some(error)).await,
+        );
+    }
+
+    let sessions = find_meet_sessions(&state.get_ref().pool, &meet.id).await;
+    if sessions
+        .iter()
+        .any(|s| s.session_date < meet.start_date || s.session_date > meet.end_date)
+    {
+        let error = "Some sessions fall outside the new dates. Delete them first.";
+        return Either::Right(
+            render_meet_form(&state, &tenant, Some(&meet), true, Some(error)).await,
+        );
+    }
+
+    if let Err(e) = update_meet(&state.get_ref().pool, &meet).await {
+        log::error!("Failed updating meet {}: {}", meet.id, e);
+        let error = "The meet could not be saved.";
+        return Either::Right(
+            render_meet_form(&state, &tenant, Some(&meet), true, Some(error)).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "UPDATE", "meet", &meet.id);
+    entry.meet = meet.id.clone();
+    entry.set_before(&existing);
+    entry.set_after(&meet);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/meets/{}/", meet.id)).see_other())
+}
+
+fn validate_meet(meet: &Meet) -> Option<&'static str> {
+    if meet.id.is_empty() || meet.name.is_empty() {
+        Some("Inform the id and name of the meet.")
+    } else if meet.end_date < meet.start_date {
+        Some("The meet can't end before it starts.")
+    } else if !COURSES.contains(&meet.course.as_str()) {
+        Some("Invalid course.")
+    } else if !MEET_TYPES.contains(&meet.meet_type.as_str()) {
+        Some("Invalid meet type.")
+    } else {
+        None
+    }
+}
+
+async fn render_meet_form(
+    state: &AppState,
+    tenant: &Tenant,
+    meet: Option<&Meet>,
+    editing: bool,
+    error: Option<&str>,
+) -> HttpResponse {
+    let clubs = find_all_clubs(&state.pool, &tenant.id).await;
+
+    let mut context = Context::new();
+    context.insert("meet", &meet);
+    context.insert("editing", &editing);
+    context.insert("clubs", &clubs);
+    context.insert("courses", &COURSES);
+    context.insert("meet_types", &MEET_TYPES);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("meet_form.html", &context).unwrap())
+}
+
+pub async fn meet_delete_view(
+    tenant: Tenant,
+    path: web::Path<MeetPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let meet = match find_followed_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(meet) => meet,
+        None => return HttpResponse::NotFound().body("Meet not found"),
+    };
+    let dependents = find_meet_dependents(&state.get_ref().pool, &tenant.id, &meet.id).await;
+
+    let mut context = Context::new();
+    context.insert("meet", &meet);
+    context.insert("dependents", &dependents);
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("meet_delete.html", &context)
+                .unwrap(),
+        )
+}
+
+pub async fn meet_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<MeetPath>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let meet = match find_followed_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(meet) => meet,
+        None => return Either::Right(HttpResponse::NotFound().body("Meet not found")),
+    };
+    let dependents = find_meet_dependents(&state.get_ref().pool, &tenant.id, &meet.id).await;
+
+    if let Err(e) = delete_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        log::error!("Failed deleting meet {}: {}", path.id, e);
+        return Either::Right(
+            HttpResponse::InternalServerError().body("The meet could not be deleted"),
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "DELETE", "meet", &meet.id);
+    entry.meet = meet.id.clone();
+    entry.set_before(&serde_json::json!({ "meet": meet, "dependents": dependents }));
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/meets").see_other())
+}
+
+pub async fn meet_sessions_view(
+    tenant: Tenant,
+    path: web::Path<MeetPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    match find_followed_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(meet) => render_meet_sessions(&state, &meet, None).await,
+        None => HttpResponse::NotFound().body("Meet not found"),
+    }
+}
+
+pub async fn meet_sessions_new(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<MeetPath>,
+    form: web::Form<MeetSessionForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let meet = match find_followed_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(meet) => meet,
+        None => return Either::Right(HttpResponse::NotFound().body("Meet not found")),
+    };
+
+    if followed_by_other_tenants(&state, &tenant, &meet).await {
+        return Either::Right(
+            render_meet_sessions(&state, &meet, Some(FOLLOWED_MEET_SESSIONS_ERROR)).await,
+        );
+    }
+
+    if form.session_date < meet.start_date || form.session_date > meet.end_date {
+        return Either::Right(
+            render_meet_sessions(
+                &state,
+                &meet,
+                Some("The session date must be within the dates of the meet."),
+            )
+            .await,
+        );
+    }
+
+    if form.period != "MORNING" && form.period != "EVENING" {
+        return Either::Right(
+            render_meet_sessions(
+                &state,
+                &meet,
+                Some("The session must be in the morning or in the evening."),
+            )
+            .await,
+        );
+    }
+
+    insert_meet_session(
+        &state.get_ref().pool,
+        &meet.id,
+        form.session_date,
+        &form.period,
+    )
+    .await;
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "session", &meet.id);
+    entry.meet = meet.id.clone();
+    entry.set_after(
+        &serde_json::json!({ "session_date": form.session_date, "period": form.period }),
+    );
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/meets/{}/sessions", meet.id)).see_other())
+}
+
+pub async fn meet_events_new(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<MeetSessionPath>,
+    form: web::Form<MeetEventForm>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let meet = match find_followed_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(meet) => meet,
+        None => return HttpResponse::NotFound().body("Meet not found"),
+    };
+
+    let sessions = find_meet_sessions(&state.get_ref().pool, &meet.id).await;
+    if !sessions.iter().any(|s| s.id == path.session) {
+        return HttpResponse::NotFound().body("Session not found");
+    }
+
+    if followed_by_other_tenants(&state, &tenant, &meet).await {
+        return render_meet_sessions(&state, &meet, Some(FOLLOWED_MEET_SESSIONS_ERROR)).await;
+    }
+
+    let event = MeetEvent {
+        id: 0,
+        session: path.session,
+        session_date: NaiveDate::MIN,
+        gender: form.gender.clone(),
+        style: form.style.clone(),
+        distance: form.distance,
+        round: form.round.clone(),
+    };
+    insert_meet_event(&state.get_ref().pool, &event).await;
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "event", &path.session.to_string());
+    entry.meet = path.id.clone();
+    entry.set_after(&event);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, format!("/meets/{}/sessions", path.id)))
+        .finish()
+}
+
+pub async fn meet_sessions_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<MeetSessionPath>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let meet = match find_followed_meet(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(meet) => meet,
+        None => return Either::Right(HttpResponse::NotFound().body("Meet not found")),
+    };
+
+    if followed_by_other_tenants(&state, &tenant, &meet).await {
+        return Either::Right(
+            render_meet_sessions(&state, &meet, Some(FOLLOWED_MEET_SESSIONS_ERROR)).await,
+        );
+    }
+
+    let sessions = find_meet_sessions(&state.get_ref().pool, &meet.id).await;
+    delete_meet_session(&state.get_ref().pool, &path.id, path.session).await;
+
+    let mut entry = AuditEntry::new(&actor, "DELETE", "session", &path.session.to_string());
+    entry.meet = path.id.clone();
+    if let Some(session) = sessions.iter().find(|s| s.id == path.session) {
+        entry.set_before(session);
+    }
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/meets/{}/sessions", path.id)).see_other())
+}
+
+const FOLLOWED_MEET_SESSIONS_ERROR: &str =
+    "Other clubs follow this meet, so its sessions and events can't change.";
+
+/// Other tenants imported their results against the sessions and events of the meet.
+async fn followed_by_other_tenants(state: &AppState, tenant: &Tenant, meet: &Meet) -> bool {
+    find_meet_dependents(&state.pool, &tenant.id, &meet.id)
+        .await
+        .num_followers
+        > 0
+}
+
+async fn render_meet_sessions(state: &AppState, meet: &Meet, error: Option<&str>) -> HttpResponse {
+    let sessions = find_meet_sessions(&state.pool, &meet.id).await;
+
+    let mut context = Context::new();
+    context.insert("meet", &meet);
+    context.insert("sessions", &sessions);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("sessions.html", &context).unwrap())
+}
+
+pub async fn clubs_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    let clubs = find_all_clubs(&state.get_ref().pool, &tenant.id).await;
+
+    let mut context = Context::new();
+    context.insert("clubs", &clubs);
+    context.insert("home_club", &tenant.club);
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("clubs.html", &context)
+                .unwrap(),
+        )
+}
+
+pub async fn clubs_save(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<ClubForm>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    save_club(&state.get_ref().pool, form.code.trim(), form.name.trim()).await;
+
+    let mut entry = AuditEntry::new(&actor, "SAVE", "club", form.code.trim());
+    entry.set_after(&serde_json::json!({ "code": form.code.trim(), "name": form.name.trim() }));
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Redirect::to("/clubs").see_other()
+}
+
+/// How the swimmer is doing in the current season.
+#[derive(Serialize)]
+struct SeasonSummary {
+    season: Season,
+    num_swims: usize,
+    num_personal_bests: usize,
+    num_meets: usize,
+}
+
+/// Best times of a season, or of each of its short course and long course phases.
+#[derive(Serialize)]
+struct SeasonBests {
+    phase: Season,
+    bests: Vec<SwimmerTime>,
+}
+
+/// Days ahead projected on the profile of a swimmer when there is no current season to end.
+const DEFAULT_FORECAST_DAYS: i64 = 90;
+
+pub async fn swimmer_view(
+    tenant: Tenant,
+    path: web::Path<SwimmerPath>,
+    query: web::Query<SwimmerQuery>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let swimmer = match find_swimmer(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(swimmer) => swimmer,
+        None => return HttpResponse::NotFound().body("Swimmer not found"),
+    };
+    let seasons = find_all_seasons(&state.get_ref().pool, &tenant.id).await;
+    let season = select_season(&seasons, &query.season);
+    let mut times =
+        find_swimmer_times(&state.get_ref().pool, &tenant.id, &swimmer, season.as_ref()).await;
+    let mut personal_bests =
+        find_best_times(&state.get_ref().pool, &tenant.id, &swimmer, None).await;
+    let age_rules = find_age_rules(&state.get_ref().pool, &tenant).await;
+    let points = find_points(&state.get_ref().pool, &tenant).await;
+    let mut season_bests: Vec<SeasonBests> = vec![];
+    for phase in season.iter().flat_map(|season| season.phases()) {
+        let mut bests =
+            find_best_times(&state.get_ref().pool, &tenant.id, &swimmer, Some(&phase)).await;
+        age_rules.assign_ages(&mut bests);
+        points.score_all(&mut bests);
+        season_bests.push(SeasonBests { phase, bests });
+    }
+    age_rules.assign_ages(&mut times);
+    age_rules.assign_ages(&mut personal_bests);
+    points.score_all(&mut times);
+    points.score_all(&mut personal_bests);
+
+    let today = Local::now().date_naive();
+    let history = find_swimmers_history(
+        &state.get_ref().pool,
+        &tenant.id,
+        std::slice::from_ref(&swimmer.id),
+        today,
+    )
+    .await;
+    let standards = find_time_standards(&state.get_ref().pool, &tenant.id, None).await;
+    let charts = event_charts(&history, &standards, age_rules.age_on(&swimmer, today));
+
+    // Projections default to the end of the current season, or a few months ahead.
+    let target = query.target.unwrap_or_else(|| {
+        select_season(&seasons, &None)
+            .map(|current| current.end_date)
+            .filter(|end| *end > today)
+            .unwrap_or(today + Duration::days(DEFAULT_FORECAST_DAYS))
+    });
+    let forecasts = forecast_all(&swimmer, &history, target);
+
+    // The summary is always about the current season, whatever season is listed.
+    let summary = match select_season(&seasons, &None) {
+        Some(current) => {
+            let season_times =
+                find_swimmer_times(&state.get_ref().pool, &tenant.id, &swimmer, Some(&current))
+                    .await;
+            let swims: Vec<&SwimmerTime> = season_times
+                .iter()
+                .filter(|st| st.dataset == "MEET_RESULTS")
+                .collect();
+            Some(SeasonSummary {
+                num_swims: swims.len(),
+                num_personal_bests: swims.iter().filter(|st| st.personal_best).count(),
+                num_meets: swims
+                    .iter()
+                    .map(|st| &st.meet.id)
+                    .collect::<HashSet<_>>()
+                    .len(),
+                season: current,
+            })
+        }
+        None => None,
+    };
+
+    let mut context = Context::new();
+    context.insert("swimmer", &swimmer);
+    context.insert("times", &times);
+    context.insert("personal_bests", &personal_bests);
+    context.insert("season_bests", &season_bests);
+    context.insert("summary", &summary);
+    context.insert("charts", &charts);
+    context.insert("forecasts", &forecasts);
+    context.insert("target", &target);
+    context.insert("target_age", &age_rules.age_on(&swimmer, target));
+    context.insert("season", &season);
+    context.insert("seasons", &seasons);
+    context.insert("points_table", points.table_name());
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("swimmer.html", &context)
+                .unwrap(),
+        )
+}
+
+pub async fn swimmer_edit_view(
+    tenant: Tenant,
+    path: web::Path<SwimmerPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    match find_swimmer(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(swimmer) => render_swimmer_edit(&state, &swimmer, None),
+        None => HttpResponse::NotFound().body("Swimmer not found"),
+    }
+}
+
+pub async fn swimmer_edit(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<SwimmerPath>,
+    form: web::Form<SwimmerForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let mut swimmer = match find_swimmer(&state.get_ref().pool, &tenant.id, &path.id).await {
+        Some(swimmer) => swimmer,
+        None => return Either::Right(HttpResponse::NotFound().body("Swimmer not found")),
+    };
+    let mut entry = AuditEntry::new(&actor, "UPDATE", "swimmer", &swimmer.id);
+    entry.swimmer = swimmer.id.clone();
+    entry.set_before(&swimmer);
+
+    swimmer.first_name = form.first_name.trim().to_string();
+    swimmer.last_name = form.last_name.trim().to_string();
+    swimmer.gender = convert_gender(&form.gender).unwrap_or_default().to_string();
+    swimmer.active = form.active.is_some();
+
+    if swimmer.first_name.is_empty() || swimmer.last_name.is_empty() {
+        return Either::Right(render_swimmer_edit(
+            &state,
+            &swimmer,
+            Some("Inform the first and last name."),
+        ));
+    }
+
+    if swimmer.gender.is_empty() {
+        return Either::Right(render_swimmer_edit(
+            &state,
+            &swimmer,
+            Some("Invalid gender."),
+        ));
+    }
+
+    swimmer.birth_date = match form.birth_date.as_str() {
+        "" => None,
+        date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                return Either::Right(render_swimmer_edit(
+                    &state,
+                    &swimmer,
+                    Some("Invalid date of birth."),
+                ))
+            }
+        },
+    };
+
+    // Dates of birth with two-digit years are easily imported a century off.
+    let today = Local::now().date_naive();
+    if swimmer
+        .birth_date
+        .is_some_and(|date| date > today || today.year() - date.year() > 100)
+    {
+        return Either::Right(render_swimmer_edit(
+            &state,
+            &swimmer,
+            Some("The date of birth is out of range."),
+        ));
+    }
+
+    if let Err(e) = update_swimmer(&state.get_ref().pool, &tenant.id, &swimmer).await {
+        log::error!("Failed updating swimmer {}: {}", swimmer.id, e);
+        return Either::Right(render_swimmer_edit(
+            &state,
+            &swimmer,
+            Some("The swimmer could not be saved."),
+        ));
+    }
+
+    entry.set_after(&swimmer);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/swimmers/{}", swimmer.id)).see_other())
+}
+
+fn render_swimmer_edit(state: &AppState, swimmer: &Swimmer, error: Option<&str>) -> HttpResponse {
+    let mut context = Context::new();
+    context.insert("swimmer", swimmer);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response.content_type("text/html; charset=utf-8").body(
+        state
+            .template
+            .render("swimmer_edit.html", &context)
+            .unwrap(),
+    )
+}
+
+pub async fn swimmer_aliases_view(
+    tenant: Tenant,
+    path: web::Path<SwimmerPath>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    render_swimmer_aliases(&state, &tenant, &path.id, None).await
+}
+
+pub async fn swimmer_aliases_new(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<SwimmerPath>,
+    form: web::Form<SwimmerAliasForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let alias = SwimmerAlias {
+        id: 0,
+        swimmer: path.id.clone(),
+        alias_id: form.alias_id.trim().to_string(),
+        first_name: form.first_name.trim().to_string(),
+        last_name: form.last_name.trim().to_string(),
+    };
+
+    if alias.alias_id.is_empty() && (alias.first_name.is_empty() || alias.last_name.is_empty()) {
+        return Either::Right(
+            render_swimmer_aliases(
+                &state,
+                &tenant,
+                &path.id,
+                Some("Inform an alternate id or a first and last name."),
+            )
+            .await,
+        );
+    }
+
+    if let Err(e) = insert_swimmer_alias(&state.get_ref().pool, &tenant.id, &alias).await {
+        log::warn!("Failed adding alias to swimmer {}: {}", path.id, e);
+        return Either::Right(
+            render_swimmer_aliases(
+                &state,
+                &tenant,
+                &path.id,
+                Some("The alternate id already belongs to a swimmer."),
+            )
+            .await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "alias", &path.id);
+    entry.swimmer = path.id.clone();
+    entry.set_after(&alias);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/swimmers/{}/aliases", path.id)).see_other())
+}
+
+pub async fn swimmer_aliases_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<SwimmerAliasPath>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let aliases = find_swimmer_aliases(&state.get_ref().pool, &tenant.id, &path.id).await;
+    delete_swimmer_alias(&state.get_ref().pool, &tenant.id, &path.id, path.alias).await;
+
+    let mut entry = AuditEntry::new(&actor, "DELETE", "alias", &path.alias.to_string());
+    entry.swimmer = path.id.clone();
+    if let Some(alias) = aliases.iter().find(|a| a.id == path.alias) {
+        entry.set_before(alias);
+    }
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Redirect::to(format!("/swimmers/{}/aliases", path.id)).see_other()
+}
+
+async fn render_swimmer_aliases(
+    state: &AppState,
+    tenant: &Tenant,
+    swimmer_id: &str,
+    error: Option<&str>,
+) -> HttpResponse {
+    let swimmer = match find_swimmer(&state.pool, &tenant.id, swimmer_id).await {
+        Some(swimmer) => swimmer,
+        None => return HttpResponse::NotFound().body("Swimmer not found"),
+    };
+    let aliases = find_swimmer_aliases(&state.pool, &tenant.id, &swimmer.id).await;
+
+    let mut context = Context::new();
+    context.insert("swimmer", &swimmer);
+    context.insert("aliases", &aliases);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response.content_type("text/html; charset=utf-8").body(
+        state
+            .template
+            .render("swimmer_aliases.html", &context)
+            .unwrap(),
+    )
+}
+
+pub async fn swimmers_merge_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    render_swimmers_merge(&state, &tenant, None).await
+}
+
+pub async fn swimmers_merge(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<SwimmerMergeForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    if form.survivor == form.merged {
+        return Either::Right(
+            render_swimmers_merge(&state, &tenant, Some("Select two different swimmers.")).await,
+        );
+    }
+
+    let survivor = find_swimmer(&state.get_ref().pool, &tenant.id, &form.survivor).await;
+    let merged = find_swimmer(&state.get_ref().pool, &tenant.id, &form.merged).await;
+    let (survivor, merged) = match (survivor, merged) {
+        (Some(survivor), Some(merged)) => (survivor, merged),
+        _ => {
+            return Either::Right(
+                render_swimmers_merge(&state, &tenant, Some("Swimmer not found.")).await,
+            )
+        }
+    };
+
+    if let Err(e) = merge_swimmers(&state.get_ref().pool, &tenant.id, &survivor, &merged).await {
+        log::error!(
+            "Failed merging swimmer {} into {}: {}",
+            merged.id,
+            survivor.id,
+            e
+        );
+        return Either::Right(
+            render_swimmers_merge(&state, &tenant, Some("The swimmers could not be merged.")).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "MERGE", "swimmer", &merged.id);
+    entry.swimmer = survivor.id.clone();
+    entry.set_before(&merged);
+    entry.set_after(&survivor);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/swimmers/merge").see_other())
+}
+
+async fn render_swimmers_merge(
+    state: &AppState,
+    tenant: &Tenant,
+    error: Option<&str>,
+) -> HttpResponse {
+    let swimmers = find_all_swimmers(&state.pool, &tenant.id, "", None, true).await;
+    let merges = find_swimmer_merges(&state.pool, &tenant.id).await;
+
+    let mut context = Context::new();
+    context.insert("swimmers", &swimmers);
+    context.insert("merges", &merges);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response.content_type("text/html; charset=utf-8").body(
+        state
+            .template
+            .render("swimmers_merge.html", &context)
+            .unwrap(),
+    )
+}
+
+/// A name waiting for review with the swimmers it most likely refers to.
+#[derive(Serialize)]
+struct NameReviewCandidates {
+    review: NameReview,
+    first_name: String,
+    last_name: String,
+    candidates: Vec<NameCandidate>,
+}
+
+#[derive(Serialize)]
+struct NameCandidate {
+    swimmer: Swimmer,
+    score: i32,
+}
+
+pub async fn name_reviews_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    render_name_reviews(&state, &tenant, None).await
+}
+
+pub async fn name_reviews_link(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<NameReviewPath>,
+    form: web::Form<NameReviewLinkForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let review = find_name_review(&state.get_ref().pool, &tenant.id, path.review).await;
+    let swimmer = find_swimmer(&state.get_ref().pool, &tenant.id, &form.swimmer).await;
+    let (review, swimmer) = match (review, swimmer) {
+        (Some(review), Some(swimmer)) => (review, swimmer),
+        _ => {
+            return Either::Right(
+                render_name_reviews(&state, &tenant, Some("Review or swimmer not found.")).await,
+            )
+        }
+    };
+
+    if let Err(e) = resolve_name_review(
+        &state.get_ref().pool,
+        &tenant.id,
+        &review,
+        &swimmer.id,
+        "LINKED",
+    )
+    .await
+    {
+        log::error!(
+            "Failed linking '{}' to {}: {}",
+            review.full_name,
+            swimmer.id,
+            e
+        );
+        return Either::Right(
+            render_name_reviews(&state, &tenant, Some("The name could not be linked.")).await,
+        );
+    }
+
+    audit_name_review(&state, &tenant, &actor, "LINK", &review, &swimmer.id).await;
+
+    // The linked times may break club records at the meet.
+    let meet = find_meet(&state.get_ref().pool, &review.meet.id).await;
+    update_club_records(&state.get_ref().pool, &tenant, &meet).await;
+
+    Either::Left(Redirect::to("/swimmers/review").see_other())
+}
+
+pub async fn name_reviews_create(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<NameReviewPath>,
+    form: web::Form<NameReviewCreateForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let review = match find_name_review(&state.get_ref().pool, &tenant.id, path.review).await {
+        Some(review) => review,
+        None => {
+            return Either::Right(
+                render_name_reviews(&state, &tenant, Some("Review not found.")).await,
+            )
+        }
+    };
+
+    let id = form.id.trim();
+    if id.is_empty() || form.first_name.trim().is_empty() || form.last_name.trim().is_empty() {
+        return Either::Right(
+            render_name_reviews(
+                &state,
+                &tenant,
+                Some("Inform the id and name of the swimmer."),
+            )
+            .await,
+        );
+    }
+
+    if find_swimmer(&state.get_ref().pool, &tenant.id, id)
+        .await
+        .is_some()
+    {
+        let error = format!(
+            "Swimmer {} already exists. Link the name to it instead.",
+            id
+        );
+        return Either::Right(render_name_reviews(&state, &tenant, Some(&error)).await);
+    }
+
+    let birth_date = match form.birth_date.as_str() {
+        "" => None,
+        date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                return Either::Right(
+                    render_name_reviews(&state, &tenant, Some("Invalid birth date.")).await,
+                )
+            }
+        },
+    };
+
+    let mut swimmer = Swimmer::new(
+        id.to_string(),
+        form.first_name.trim().to_string(),
+        form.last_name.trim().to_string(),
+    );
+    swimmer.gender = convert_gender(&form.gender).unwrap_or_default().to_string();
+    swimmer.birth_date = birth_date;
+
+    if let Err(e) = insert_swimmer(&state.get_ref().pool, &tenant.id, &swimmer).await {
+        log::error!("Failed creating swimmer {}: {}", swimmer.id, e);
+        return Either::Right(
+            render_name_reviews(&state, &tenant, Some("The swimmer could not be created.")).await,
+        );
+    }
+
+    if !review.club.is_empty() {
+        let since = find_meet(&state.get_ref().pool, &review.meet.id)
+            .await
+            .start_date;
+        insert_club_membership(
+            &state.get_ref().pool,
+            &tenant.id,
+            &swimmer.id,
+            &review.club,
+            since,
+        )
+        .await;
+    }
+
+    if let Err(e) = resolve_name_review(
+        &state.get_ref().pool,
+        &tenant.id,
+        &review,
+        &swimmer.id,
+        "CREATED",
+    )
+    .await
+    {
+        log::error!(
+            "Failed linking '{}' to {}: {}",
+            review.full_name,
+            swimmer.id,
+            e
+        );
+        return Either::Right(
+            render_name_reviews(&state, &tenant, Some("The name could not be linked.")).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "swimmer", &swimmer.id);
+    entry.meet = review.meet.id.clone();
+    entry.swimmer = swimmer.id.clone();
+    entry.set_after(&swimmer);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+    audit_name_review(&state, &tenant, &actor, "LINK", &review, &swimmer.id).await;
+
+    // The linked times may break club records at the meet.
+    let meet = find_meet(&state.get_ref().pool, &review.meet.id).await;
+    update_club_records(&state.get_ref().pool, &tenant, &meet).await;
+
+    Either::Left(Redirect::to("/swimmers/review").see_other())
+}
+
+pub async fn name_reviews_ignore(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<NameReviewPath>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let review = find_name_review(&state.get_ref().pool, &tenant.id, path.review).await;
+    ignore_name_review(&state.get_ref().pool, &tenant.id, path.review).await;
+
+    if let Some(review) = review {
+        audit_name_review(&state, &tenant, &actor, "IGNORE", &review, "").await;
+    }
+
+    Redirect::to("/swimmers/review").see_other()
+}
+
+async fn audit_name_review(
+    state: &AppState,
+    tenant: &Tenant,
+    actor: &Actor,
+    action: &str,
+    review: &NameReview,
+    swimmer_id: &str,
+) {
+    let mut entry = AuditEntry::new(actor, action, "name_review", &review.id.to_string());
+    entry.meet = review.meet.id.clone();
+    entry.swimmer = swimmer_id.to_string();
+    entry.set_before(review);
+    if !swimmer_id.is_empty() {
+        entry.set_after(
+            &serde_json::json!({ "full_name": review.full_name, "swimmer": swimmer_id }),
+        );
+    }
+    insert_audit(&state.pool, &tenant.id, &entry).await;
+}
+
+async fn render_name_reviews(
+    state: &AppState,
+    tenant: &Tenant,
+    error: Option<&str>,
+) -> HttpResponse {
+    let reviews = find_pending_name_reviews(&state.pool, &tenant.id).await;
+    let swimmers = find_all_swimmers(&state.pool, &tenant.id, "", None, true).await;
+    let aliases = find_all_swimmer_aliases(&state.pool, &tenant.id).await;
+
+    let reviews: Vec<NameReviewCandidates> = reviews
+        .into_iter()
+        .map(|review| {
+            let query = NameQuery {
+                full_name: review.full_name.clone(),
+                gender: review.gender.clone(),
+                birth_year: review.birth_year,
+                club: review.club.clone(),
+            };
+            let candidates = match resolve(&query, &swimmers, &aliases) {
+                Resolution::Accepted(swimmer) => vec![NameCandidate {
+                    swimmer: swimmer.clone(),
+                    score: 100,
+                }],
+                Resolution::Review(scored) => scored
+                    .into_iter()
+                    .take(5)
+                    .map(|s| NameCandidate {
+                        swimmer: s.swimmer.clone(),
+                        score: (s.score * 100.0).round() as i32,
+                    })
+                    .collect(),
+            };
+            let (first_name, last_name) = split_full_name(&review.full_name, false);
+            NameReviewCandidates {
+                review,
+                first_name,
+                last_name,
+                candidates,
+            }
+        })
+        .collect();
+
+    let mut context = Context::new();
+    context.insert("reviews", &reviews);
+    context.insert("swimmers", &swimmers);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response.content_type("text/html; charset=utf-8").body(
+        state
+            .template
+            .render("name_reviews.html", &context)
+            .unwrap(),
+    )
+}
+
+pub async fn groups_view(
+    tenant: Tenant,
+    actor: Actor,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    render_groups(&state, &tenant, &actor, None).await
+}
+
+pub async fn groups_new(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<GroupForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let name = form.name.trim();
+    if name.is_empty() {
+        return Either::Right(
+            render_groups(
+                &state,
+                &tenant,
+                &actor,
+                Some("Inform the name of the group."),
+            )
+            .await,
+        );
+    }
+
+    if let Err(e) = insert_training_group(&state.get_ref().pool, &tenant.id, name).await {
+        log::error!("Failed creating group {}: {}", name, e);
+        return Either::Right(
+            render_groups(&state, &tenant, &actor, Some("The group already exists.")).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "group", name);
+    entry.set_after(&serde_json::json!({ "name": name }));
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/groups").see_other())
+}
+
+pub async fn coaches_new(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<CoachForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let name = form.name.trim();
+    if name.is_empty() {
+        return Either::Right(
+            render_groups(
+                &state,
+                &tenant,
+                &actor,
+                Some("Inform the name of the coach."),
+            )
+            .await,
+        );
+    }
+
+    if let Err(e) = insert_coach(&state.get_ref().pool, &tenant.id, name, form.email.trim()).await {
+        log::error!("Failed creating coach {}: {}", name, e);
+        return Either::Right(
+            render_groups(&state, &tenant, &actor, Some("The coach already exists.")).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "coach", name);
+    entry.set_after(&serde_json::json!({ "name": name, "email": form.email.trim() }));
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/groups").see_other())
+}
+
+async fn render_groups(
+    state: &AppState,
+    tenant: &Tenant,
+    actor: &Actor,
+    error: Option<&str>,
+) -> HttpResponse {
+    let groups = find_all_training_groups(&state.pool, &tenant.id).await;
+    let coaches = find_all_coaches(&state.pool, &tenant.id).await;
+
+    let mut context = Context::new();
+    context.insert("groups", &groups);
+    context.insert("coaches", &coaches);
+    context.insert("actor", actor);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("groups.html", &context).unwrap())
+}
+
+pub async fn group_view(
+    tenant: Tenant,
+    path: web::Path<GroupPath>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    render_group(&state, &tenant, path.group, None).await
+}
+
+pub async fn group_members_new(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<GroupPath>,
+    form: web::Form<GroupMemberForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    if find_training_group(&state.get_ref().pool, &tenant.id, path.group)
+        .await
+        .is_none()
+    {
+        return Either::Right(HttpResponse::NotFound().body("Group not found"));
+    }
+
+    if find_swimmer(&state.get_ref().pool, &tenant.id, &form.swimmer)
+        .await
+        .is_none()
+    {
+        return Either::Right(
+            render_group(&state, &tenant, path.group, Some("Swimmer not found.")).await,
+        );
+    }
+
+    if let Err(e) = insert_group_member(
+        &state.get_ref().pool,
+        &tenant.id,
+        path.group,
+        &form.swimmer,
+        form.start_date,
+    )
+    .await
+    {
+        log::error!(
+            "Failed adding swimmer {} to group {}: {}",
+            form.swimmer,
+            path.group,
+            e
+        );
+        return Either::Right(
+            render_group(
+                &state,
+                &tenant,
+                path.group,
+                Some("The swimmer could not be added."),
+            )
+            .await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "JOIN", "group", &path.group.to_string());
+    entry.swimmer = form.swimmer.clone();
+    entry.set_after(&serde_json::json!({ "group": path.group, "start_date": form.start_date }));
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/groups/{}", path.group)).see_other())
+}
+
+pub async fn group_members_end(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<GroupMemberPath>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let members = find_group_members(&state.get_ref().pool, &tenant.id, path.group).await;
+    let today = Local::now().date_naive();
+    end_group_member(
+        &state.get_ref().pool,
+        &tenant.id,
+        path.group,
+        path.member,
+        today,
+    )
+    .await;
+
+    if let Some(member) = members.iter().find(|m| m.id == path.member) {
+        let mut entry = AuditEntry::new(&actor, "LEAVE", "group", &path.group.to_string());
+        entry.swimmer = member.swimmer.id.clone();
+        entry.set_before(&serde_json::json!({ "group": path.group, "end_date": member.end_date }));
+        entry.set_after(&serde_json::json!({ "group": path.group, "end_date": today }));
+        insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+    }
+
+    Redirect::to(format!("/groups/{}", path.group)).see_other()
+}
+
+pub async fn group_coaches_new(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<GroupPath>,
+    form: web::Form<GroupCoachForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    if !group_and_coach_exist(&state, &tenant, path.group, form.coach).await {
+        return Either::Right(HttpResponse::NotFound().body("Group or coach not found"));
+    }
+
+    assign_group_coach(&state.get_ref().pool, &tenant.id, path.group, form.coach).await;
+
+    let mut entry = AuditEntry::new(&actor, "ASSIGN", "group", &path.group.to_string());
+    entry.set_after(&serde_json::json!({ "coach": form.coach }));
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/groups/{}", path.group)).see_other())
+}
+
+pub async fn group_coaches_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<GroupCoachPath>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    if !group_and_coach_exist(&state, &tenant, path.group, path.coach).await {
+        return Either::Right(HttpResponse::NotFound().body("Group or coach not found"));
+    }
+
+    unassign_group_coach(&state.get_ref().pool, &tenant.id, path.group, path.coach).await;
+
+    let mut entry = AuditEntry::new(&actor, "UNASSIGN", "group", &path.group.to_string());
+    entry.set_before(&serde_json::json!({ "coach": path.coach }));
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to(format!("/groups/{}", path.group)).see_other())
+}
+
+/// Keeps the audit from recording assignments of groups or coaches of other tenants.
+async fn group_and_coach_exist(state: &AppState, tenant: &Tenant, group: i32, coach: i32) -> bool {
+    find_training_group(&state.pool, &tenant.id, group)
+        .await
+        .is_some()
+        && find_coach(&state.pool, &tenant.id, coach).await.is_some()
+}
+
+async fn render_group(
+    state: &AppState,
+    tenant: &Tenant,
+    group_id: i32,
+    error: Option<&str>,
+) -> HttpResponse {
+    let group = match find_training_group(&state.pool, &tenant.id, group_id).await {
+        Some(group) => group,
+        None => return HttpResponse::NotFound().body("Group not found"),
+    };
+    let members = find_group_members(&state.pool, &tenant.id, group.id).await;
+    let swimmers = find_all_swimmers(&state.pool, &tenant.id, &tenant.club, None, false).await;
+    let coaches = find_all_coaches(&state.pool, &tenant.id).await;
+
+    let mut context = Context::new();
+    context.insert("group", &group);
+    context.insert("members", &members);
+    context.insert("swimmers", &swimmers);
+    context.insert("coaches", &coaches);
+    context.insert("today", &Local::now().date_naive());
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("group.html", &context).unwrap())
+}
+
+pub async fn seasons_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    render_seasons(&state, &tenant, None).await
+}
+
+pub async fn seasons_new(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<SeasonForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    if form.name.trim().is_empty() {
+        return Either::Right(
+            render_seasons(&state, &tenant, Some("Inform the name of the season.")).await,
+        );
+    }
+
+    if form.end_date <= form.start_date {
+        return Either::Right(
+            render_seasons(
+                &state,
+                &tenant,
+                Some("The season must end after it starts."),
+            )
+            .await,
+        );
+    }
+
+    let long_course_start = match form.long_course_start.as_str() {
+        "" => None,
+        date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) if date > form.start_date && date <= form.end_date => Some(date),
+            _ => {
+                let error = "The long course phase must start within the season.";
+                return Either::Right(render_seasons(&state, &tenant, Some(error)).await);
+            }
+        },
+    };
+
+    let seasons = find_all_seasons(&state.get_ref().pool, &tenant.id).await;
+    if seasons
+        .iter()
+        .any(|s| s.start_date <= form.end_date && form.start_date <= s.end_date)
+    {
+        return Either::Right(
+            render_seasons(&state, &tenant, Some("Seasons can't overlap.")).await,
+        );
+    }
+
+    let season = Season {
+        id: 0,
+        name: form.name.trim().to_string(),
+        start_date: form.start_date,
+        end_date: form.end_date,
+        long_course_start,
+    };
+    if let Err(e) = insert_season(&state.get_ref().pool, &tenant.id, &season).await {
+        log::error!("Failed creating season {}: {}", season.name, e);
+        return Either::Right(
+            render_seasons(&state, &tenant, Some("The season already exists.")).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "season", &season.name);
+    entry.set_after(&season);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/seasons").see_other())
+}
+
+pub async fn seasons_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<SeasonPath>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let seasons = find_all_seasons(&state.get_ref().pool, &tenant.id).await;
+    delete_season(&state.get_ref().pool, &tenant.id, path.season).await;
+
+    if let Some(season) = seasons.iter().find(|s| s.id == path.season) {
+        let mut entry = AuditEntry::new(&actor, "DELETE", "season", &season.name);
+        entry.set_before(season);
+        insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+    }
+
+    Redirect::to("/seasons").see_other()
+}
+
+async fn render_seasons(state: &AppState, tenant: &Tenant, error: Option<&str>) -> HttpResponse {
+    let seasons = find_all_seasons(&state.pool, &tenant.id).await;
+
+    // The form suggests the season after the latest one, from September to August.
+    let next_year = seasons
+        .first()
+        .map(|s| s.end_date.year())
+        .unwrap_or_else(|| {
+            let today = Local::now().date_naive();
+            if today.month() >= 9 {
+                today.year()
+            } else {
+                today.year() - 1
+            }
+        });
+
+    let mut context = Context::new();
+    context.insert("seasons", &seasons);
+    context.insert("next_start", &NaiveDate::from_ymd_opt(next_year, 9, 1));
+    context.insert("next_end", &NaiveDate::from_ymd_opt(next_year + 1, 8, 31));
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("seasons.html", &context).unwrap())
+}
+
+/// Selects the coach making the changes from this browser. An empty coach clears the selection.
+pub async fn coaches_act(
+    tenant: Tenant,
+    form: web::Form<CoachActForm>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let coach = match form.coach.parse::<i32>() {
+        Ok(coach_id) => find_coach(&state.get_ref().pool, &tenant.id, coach_id).await,
+        Err(_) => None,
+    };
+
+    let mut cookie = Cookie::build(
+        ACTOR_COOKIE,
+        coach.map(|c| c.id.to_string()).unwrap_or_default(),
+    )
+    .path("/")
+    .http_only(true)
+    .permanent()
+    .finish();
+    if cookie.value().is_empty() {
+        cookie.make_removal();
+    } else {
+        // The coach can't be picked by editing the cookie.
+        let mut jar = CookieJar::new();
+        jar.private_mut(&state.cookie_key).add(cookie);
+        cookie = jar.get(ACTOR_COOKIE).cloned().expect("Encrypted cookie");
+    }
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, "/groups"))
+        .cookie(cookie)
+        .finish()
+}
+
+pub async fn meet_entries_rollback(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<MeetPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    rollback_meet_import(&state, &tenant, &actor, &path.id, "MEET_ENTRIES", "entries").await
+}
+
+pub async fn meet_results_rollback(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<MeetPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    rollback_meet_import(&state, &tenant, &actor, &path.id, "MEET_RESULTS", "results").await
+}
+
+async fn rollback_meet_import(
+    state: &AppState,
+    tenant: &Tenant,
+    actor: &Actor,
+    meet_id: &str,
+    dataset: &str,
+    page: &str,
+) -> HttpResponse {
+    let history: Vec<ImportHistory> = find_import_history(&state.pool, &tenant.id, meet_id)
+        .await
+        .into_iter()
+        .filter(|ih| ih.dataset == dataset)
+        .collect();
+
+    let num_times = match rollback_import(&state.pool, &tenant.id, meet_id, dataset).await {
+        Ok(num_times) => num_times,
+        Err(e) => {
+            log::error!("Failed rolling back {} of meet {}: {}", dataset, meet_id, e);
+            return HttpResponse::InternalServerError().body("The import could not be rolled back");
+        }
+    };
+
+    let mut entry = AuditEntry::new(actor, "ROLLBACK", "import", dataset);
+    entry.meet = meet_id.to_string();
+    entry.set_before(&serde_json::json!({
+        "dataset": dataset,
+        "imports": history.len(),
+        "num_times": num_times,
+    }));
+    insert_audit(&state.pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, format!("/meets/{}/{}", meet_id, page)))
+        .finish()
+}
+
+/// A field of an audited entity that changed, formatted to be read.
+#[derive(Serialize)]
+struct AuditChange {
+    field: String,
+    before: String,
+    after: String,
+}
+
+#[derive(Serialize)]
+struct AuditRow {
+    entry: AuditEntry,
+    changes: Vec<AuditChange>,
+}
+
+pub async fn audit_view(
+    tenant: Tenant,
+    actor: Actor,
+    query: web::Query<AuditQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let meet = query.meet.as_deref().filter(|m| !m.is_empty());
+    let swimmer = query.swimmer.as_deref().filter(|s| !s.is_empty());
+    let entries = find_audit_entries(&state.get_ref().pool, &tenant.id, meet, swimmer, 500).await;
+    let meets = find_all_meets(&state.get_ref().pool, &tenant.id, None).await;
+    let swimmers = find_all_swimmers(&state.get_ref().pool, &tenant.id, "", None, true).await;
+
+    let rows: Vec<AuditRow> = entries
+        .into_iter()
+        .map(|entry| AuditRow {
+            changes: audit_changes(&entry.before, &entry.after),
+            entry,
+        })
+        .collect();
+
+    let mut context = Context::new();
+    context.insert("rows", &rows);
+    context.insert("meets", &meets);
+    context.insert("swimmers", &swimmers);
+    context.insert("meet", &meet);
+    context.insert("swimmer", &swimmer);
+    context.insert("actor", &actor);
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("audit.html", &context)
+                .unwrap(),
+        )
+}
+
+/// Lists the fields that differ between the values before and after a change.
+fn audit_changes(before: &str, after: &str) -> Vec<AuditChange> {
+    let parse = |json: &str| match serde_json::from_str::<Value>(json) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    let (before, after) = (parse(before), parse(after));
+
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter(|field| before.get(*field) != after.get(*field))
+        .map(|field| AuditChange {
+            field: field.clone(),
+            before: before.get(field).map(format_value).unwrap_or_default(),
+            after: after.get(field).map(format_value).unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub async fn conversions_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    render_conversions(&state, &tenant, None).await
+}
+
+pub async fn conversions_save(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<ConversionForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let conversions =
+        CourseConversions::new(find_course_conversions(&state.get_ref().pool, &tenant.id).await);
+    let current = match conversions.all().iter().find(|c| form.matches(c)) {
+        Some(current) => current,
+        None => {
+            return Either::Right(render_conversions(&state, &tenant, Some("Unknown event.")).await)
+        }
+    };
+
+    // Courses differ by a few percent, except yard events converted to shorter metre events.
+    if !(0.5..=1.5).contains(&form.factor) {
+        let error = "The factor must be between 0.5 and 1.5.";
+        return Either::Right(render_conversions(&state, &tenant, Some(error)).await);
+    }
+
+    let conversion = CourseConversion {
+        factor: form.factor,
+        custom: true,
+        ..current.clone()
+    };
+    save_course_conversion(&state.get_ref().pool, &tenant.id, &conversion).await;
+
+    let mut entry = AuditEntry::new(&actor, "UPDATE", "conversion", &conversion.style);
+    entry.set_before(current);
+    entry.set_after(&conversion);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/conversions").see_other())
+}
+
+pub async fn conversions_reset(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<ConversionForm>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let conversions = find_course_conversions(&state.get_ref().pool, &tenant.id).await;
+    if let Some(custom) = conversions.iter().find(|c| form.matches(c)) {
+        delete_course_conversion(&state.get_ref().pool, &tenant.id, custom).await;
+
+        let mut entry = AuditEntry::new(&actor, "RESET", "conversion", &custom.style);
+        entry.set_before(custom);
+        insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+    }
+
+    Redirect::to("/conversions").see_other()
+}
+
+impl ConversionForm {
+    fn matches(&self, conversion: &CourseConversion) -> bool {
+        conversion.gender == self.gender
+            && conversion.style == self.style
+            && conversion.from_course == self.from_course
+            && conversion.from_distance == self.from_distance
+            && conversion.to_course == self.to_course
+    }
+}
+
+async fn render_conversions(
+    state: &AppState,
+    tenant: &Tenant,
+    error: Option<&str>,
+) -> HttpResponse {
+    let conversions =
+        CourseConversions::new(find_course_conversions(&state.pool, &tenant.id).await);
+
+    let mut context = Context::new();
+    context.insert("conversions", conversions.all());
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("conversions.html", &context).unwrap())
+}
+
+pub async fn standards_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    render_standards(&state, &tenant, None).await
+}
+
+pub async fn standards_new(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<StandardSetForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let name = form.name.trim();
+    if name.is_empty() {
+        return Either::Right(
+            render_standards(&state, &tenant, Some("Inform the name of the standards.")).await,
+        );
+    }
+
+    let parse_date = |date: &str| match date {
+        "" => Ok(None),
+        date => NaiveDate::parse_from_str(date, "%Y-%m-%d").map(Some),
+    };
+    let (qualifying_start, qualifying_end) = match (
+        parse_date(&form.qualifying_start),
+        parse_date(&form.qualifying_end),
+    ) {
+        (Ok(start), Ok(end)) => (start, end),
+        _ => return Either::Right(render_standards(&state, &tenant, Some("Invalid date.")).await),
+    };
+
+    if let (Some(start), Some(end)) = (qualifying_start, qualifying_end) {
+        if end < start {
+            let error = "The qualifying window can't end before it starts.";
+            return Either::Right(render_standards(&state, &tenant, Some(error)).await);
+        }
+    }
+
+    let set = StandardSet {
+        id: 0,
+        name: name.to_string(),
+        qualifying_start,
+        qualifying_end,
+        num_standards: 0,
+        tracked: false,
+    };
+    if let Err(e) = insert_standard_set(&state.get_ref().pool, &tenant.id, &set).await {
+        log::error!("Failed creating standards {}: {}", set.name, e);
+        return Either::Right(
+            render_standards(&state, &tenant, Some("The standards already exist.")).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "standards", &set.name);
+    entry.set_after(&set);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/standards").see_other())
+}
+
+pub async fn standards_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<StandardSetPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let set = match find_standard_set(&state.get_ref().pool, &tenant.id, path.set).await {
+        Some(set) => set,
+        None => return HttpResponse::NotFound().body("Standards not found"),
+    };
+
+    if let Err(e) = delete_standard_set(&state.get_ref().pool, &tenant.id, set.id).await {
+        log::error!("Failed deleting standards {}: {}", set.name, e);
+        return HttpResponse::InternalServerError().body("The standards could not be deleted");
+    }
+
+    let mut entry = AuditEntry::new(&actor, "DELETE", "standards", &set.name);
+    entry.set_before(&set);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, "/standards"))
+        .finish()
+}
+
+async fn render_standards(state: &AppState, tenant: &Tenant, error: Option<&str>) -> HttpResponse {
+    let sets = find_all_standard_sets(&state.pool, &tenant.id).await;
+
+    let mut context = Context::new();
+    context.insert("sets", &sets);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("standards.html", &context).unwrap())
+}
+
+pub async fn standard_set_view(
+    tenant: Tenant,
+    path: web::Path<StandardSetPath>,
+    query: web::Query<StandardSetQuery>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let set = match find_standard_set(&state.get_ref().pool, &tenant.id, path.set).await {
+        Some(set) => set,
+        None => return HttpResponse::NotFound().body("Standards not found"),
+    };
+    let standards = find_time_standards(&state.get_ref().pool, &tenant.id, Some(set.id)).await;
+
+    let mut context = Context::new();
+    context.insert("set", &set);
+    context.insert("standards", &standards);
+    context.insert("skipped", &query.skipped.unwrap_or_default());
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("standard_set.html", &context)
+                .unwrap(),
+        )
+}
+
+/// Points of the tenant, with its active points table or World Aquatics points.
+pub async fn find_points(conn: &PgPool, tenant: &Tenant) -> Points {
+    let conversions = CourseConversions::new(find_course_conversions(conn, &tenant.id).await);
+    let table: Box<dyn PointsTable> = match find_points_tables(conn, &tenant.id)
+        .await
+        .into_iter()
+        .find(|t| t.active)
+    {
+        Some(table) => {
+            let rows = find_points_rows(conn, table.id).await;
+            match table.kind.as_str() {
+                "SCORE" => Box::new(ScoreTable {
+                    name: table.name,
+                    rows,
+                }),
+                _ => Box::new(BaseTimeTable {
+                    name: table.name,
+                    rows,
+                }),
+            }
+        }
+        None => Box::new(WorldAquatics),
+    };
+    Points::new(table, conversions)
+}
+
+pub async fn points_view(
+    tenant: Tenant,
+    query: web::Query<PointsQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let tables = find_points_tables(&state.get_ref().pool, &tenant.id).await;
+
+    let mut context = Context::new();
+    context.insert("tables", &tables);
+    context.insert("world_aquatics", WorldAquatics.name());
+    context.insert("skipped", &query.skipped.unwrap_or_default());
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("points.html", &context)
+                .unwrap(),
+        )
+}
+
+pub async fn points_activate(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<PointsActivateForm>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let tables = find_points_tables(&state.get_ref().pool, &tenant.id).await;
+    let table = match form.table.as_str() {
+        "" => None,
+        id => match tables.iter().find(|t| t.id.to_string() == id) {
+            Some(table) => Some(table),
+            None => return HttpResponse::NotFound().body("Points table not found"),
+        },
+    };
+
+    activate_points_table(&state.get_ref().pool, &tenant.id, table.map(|t| t.id)).await;
+
+    let name = table
+        .map(|t| t.name.as_str())
+        .unwrap_or_else(|| WorldAquatics.name());
+    let mut entry = AuditEntry::new(&actor, "ACTIVATE", "points_table", name);
+    entry.set_before(&tables.iter().find(|t| t.active).map(|t| &t.name));
+    entry.set_after(&name);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, "/points"))
+        .finish()
+}
+
+pub async fn points_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<PointsTablePath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let tables = find_points_tables(&state.get_ref().pool, &tenant.id).await;
+    let table = match tables.into_iter().find(|t| t.id == path.table) {
+        Some(table) => table,
+        None => return HttpResponse::NotFound().body("Points table not found"),
+    };
+
+    if let Err(e) = delete_points_table(&state.get_ref().pool, &tenant.id, table.id).await {
+        log::error!("Failed deleting points table {}: {}", table.name, e);
+        return HttpResponse::InternalServerError().body("The points table could not be deleted");
+    }
+
+    let mut entry = AuditEntry::new(&actor, "DELETE", "points_table", &table.name);
+    entry.set_before(&table);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, "/points"))
+        .finish()
+}
+
+pub async fn find_age_rules(conn: &PgPool, tenant: &Tenant) -> AgeRules {
+    AgeRules::new(
+        find_age_rule(conn, &tenant.id).await,
+        find_age_groups(conn, &tenant.id).await,
+    )
+}
+
+pub async fn ages_view(tenant: Tenant, state: web::Data<AppState>) -> impl Responder {
+    render_ages(&state, &tenant, None).await
+}
+
+pub async fn age_rule_save(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<AgeRuleForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    if !AGE_RULES.contains(&form.rule.as_str()) {
+        return Either::Right(render_ages(&state, &tenant, Some("Unknown age rule.")).await);
+    }
+
+    let before = find_age_rules(&state.get_ref().pool, &tenant).await;
+    save_age_rule(&state.get_ref().pool, &tenant.id, &form.rule).await;
+
+    let mut entry = AuditEntry::new(&actor, "UPDATE", "age_rule", &tenant.id);
+    entry.set_before(&before.rule);
+    entry.set_after(&form.rule);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/ages").see_other())
+}
+
+pub async fn age_groups_new(
+    tenant: Tenant,
+    actor: Actor,
+    form: web::Form<AgeGroupForm>,
+    state: web::Data<AppState>,
+) -> Either<Redirect, HttpResponse> {
+    let name = form.name.trim();
+    if name.is_empty() {
+        return Either::Right(
+            render_ages(&state, &tenant, Some("Inform the name of the age group.")).await,
+        );
+    }
+
+    let parse_age = |age: &str| match age.trim() {
+        "" => Ok(None),
+        age => age.parse::<i32>().map(Some),
+    };
+    let (min_age, max_age) = match (parse_age(&form.min_age), parse_age(&form.max_age)) {
+        (Ok(min_age), Ok(max_age)) => (min_age, max_age),
+        _ => return Either::Right(render_ages(&state, &tenant, Some("Invalid age.")).await),
+    };
+    match (min_age, max_age) {
+        (None, None) => {
+            let error = "Inform the minimum or the maximum age of the group.";
+            return Either::Right(render_ages(&state, &tenant, Some(error)).await);
+        }
+        (Some(min), Some(max)) if max < min => {
+            let error = "The maximum age can't be lower than the minimum age.";
+            return Either::Right(render_ages(&state, &tenant, Some(error)).await);
+        }
+        _ => {}
+    }
+
+    let age_group = AgeGroup {
+        id: 0,
+        name: name.to_string(),
+        min_age,
+        max_age,
+    };
+    if let Err(e) = insert_age_group(&state.get_ref().pool, &tenant.id, &age_group).await {
+        log::error!("Failed creating age group {}: {}", age_group.name, e);
+        return Either::Right(
+            render_ages(&state, &tenant, Some("The age group already exists.")).await,
+        );
+    }
+
+    let mut entry = AuditEntry::new(&actor, "CREATE", "age_group", &age_group.name);
+    entry.set_after(&age_group);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    Either::Left(Redirect::to("/ages").see_other())
+}
+
+pub async fn age_groups_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<AgeGroupPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let age_groups = find_age_groups(&state.get_ref().pool, &tenant.id).await;
+    let age_group = match age_groups.into_iter().find(|g| g.id == path.age_group) {
+        Some(age_group) => age_group,
+        None => return HttpResponse::NotFound().body("Age group not found"),
+    };
+
+    delete_age_group(&state.get_ref().pool, &tenant.id, age_group.id).await;
+
+    let mut entry = AuditEntry::new(&actor, "DELETE", "age_group", &age_group.name);
+    entry.set_before(&age_group);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, "/ages"))
+        .finish()
+}
+
+async fn render_ages(state: &AppState, tenant: &Tenant, error: Option<&str>) -> HttpResponse {
+    let age_rules = find_age_rules(&state.pool, tenant).await;
+
+    let mut context = Context::new();
+    context.insert("rule", &age_rules.rule);
+    context.insert("rules", &AGE_RULES);
+    context.insert("age_groups", &age_rules.groups);
+    context.insert("default_groups", &age_rules.default_groups);
+    context.insert("error", &error);
+
+    let mut response = match error {
+        Some(_) => HttpResponse::BadRequest(),
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(state.template.render("ages.html", &context).unwrap())
+}
+
+/// How far the best time of a swimmer in an event is from the next standard, the slowest one not
+/// achieved yet.
+#[derive(Serialize)]
+struct StandardGap {
+    best: SwimmerTime,
+    standard: Option<TimeStandard>,
+    gap: Option<i32>,
+    percent: Option<f64>,
+}
+
+pub async fn gaps_view(
+    tenant: Tenant,
+    query: web::Query<GapQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let pool = &state.get_ref().pool;
+    let club = query.club.clone().unwrap_or_else(|| tenant.club.clone());
+    let group = parse_group(&query.group);
+    let course = query.course.clone().unwrap_or_default();
+    let groups = find_all_training_groups(pool, &tenant.id).await;
+
+    let swimmers = find_all_swimmers(pool, &tenant.id, &club, group, false).await;
+    let swimmer_ids: Vec<String> = swimmers.into_iter().map(|s| s.id).collect();
+    let bests = find_swimmers_best_times(pool, &tenant.id, &swimmer_ids, &course).await;
+
+    // Standards whose qualifying window is over can't be achieved anymore.
+    let today = Local::now().date_naive();
+    let open_sets: Vec<i32> = find_all_standard_sets(pool, &tenant.id)
+        .await
+        .into_iter()
+        .filter(|set| set.qualifying_end.is_none_or(|end| end >= today))
+        .map(|set| set.id)
+        .collect();
+    let standards: Vec<TimeStandard> = find_time_standards(pool, &tenant.id, None)
+        .await
+        .into_iter()
+        .filter(|ts| open_sets.contains(&ts.standard_set))
+        .collect();
+    let age_rules = find_age_rules(pool, &tenant).await;
+
+    let mut gaps: Vec<StandardGap> = bests
+        .into_iter()
+        .filter_map(|best| {
+            let age = age_rules.age_on(&best.swimmer, today);
+            let mut event_standards = standards
+                .iter()
+                .filter(|ts| {
+                    ts.style == best.style
+                        && ts.distance == best.distance
+                        && ts.course == best.course
+                })
+                .filter(|ts| ts.gender == best.swimmer.gender && ts.applies_to(age))
+                .peekable();
+            event_standards.peek()?;
+
+            let standard = event_standards
+                .filter(|ts| ts.time < best.time)
+                .max_by_key(|ts| ts.time)
+                .cloned();
+            let gap = standard.as_ref().map(|ts| best.time - ts.time);
+            Some(StandardGap {
+                percent: standard
+                    .as_ref()
+                    .map(|ts| (best.time - ts.time) as f64 * 100.0 / ts.time as f64),
+                gap,
+                standard,
+                best,
+            })
+        })
+        .collect();
+    // Near-misses first, and events with every standard achieved last.
+    gaps.sort_by(|a, b| match (a.percent, b.percent) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+
+    let mut context = Context::new();
+    context.insert("gaps", &gaps);
+    context.insert("club", &club);
+    context.insert("home_club", &tenant.club);
+    context.insert("group", &group);
+    context.insert("groups", &groups);
+    context.insert("course", &course);
+    context.insert("courses", &COURSES);
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("gaps.html", &context)
+                .unwrap(),
+        )
+}
+
+pub async fn standards_track(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<StandardSetPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let set = match find_standard_set(&state.get_ref().pool, &tenant.id, path.set).await {
+        Some(set) => set,
+        None => return HttpResponse::NotFound().body("Standards not found"),
+    };
+
+    update_standard_set_tracking(&state.get_ref().pool, &tenant.id, set.id, !set.tracked).await;
+
+    let mut entry = AuditEntry::new(&actor, "UPDATE", "standards", &set.name);
+    entry.set_before(&set);
+    entry.set_after(&StandardSet {
+        tracked: !set.tracked,
+        ..set.clone()
+    });
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, "/standards"))
+        .finish()
+}
+
+/// The standards a swimmer achieved, with the results that first achieved them.
+#[derive(Serialize)]
+struct SwimmerQualifications {
+    swimmer: Swimmer,
+    qualifications: Vec<Qualification>,
+}
+
+pub async fn qualifications_view(
+    tenant: Tenant,
+    query: web::Query<QualificationQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let pool = &state.get_ref().pool;
+    let club = query.club.clone().unwrap_or_else(|| tenant.club.clone());
+    let group = parse_group(&query.group);
+    let set: Option<i32> = query.set.as_ref().and_then(|set| set.parse().ok());
+    let groups = find_all_training_groups(pool, &tenant.id).await;
+
+    let all_sets = find_all_standard_sets(pool, &tenant.id).await;
+    let sets: Vec<StandardSet> = all_sets
+        .iter()
+        .filter(|s| match set {
+            Some(set) => s.id == set,
+            None => s.tracked,
+        })
+        .cloned()
+        .collect();
+    let standards: Vec<TimeStandard> = find_time_standards(pool, &tenant.id, None)
+        .await
+        .into_iter()
+        .filter(|ts| sets.iter().any(|s| s.id == ts.standard_set))
+        .collect();
+
+    let swimmers = find_all_swimmers(pool, &tenant.id, &club, group, false).await;
+    let swimmer_ids: Vec<String> = swimmers.iter().map(|s| s.id.clone()).collect();
+    let today = Local::now().date_naive();
+    let mut results: Vec<SwimmerTime> =
+        find_swimmers_history(pool, &tenant.id, &swimmer_ids, today)
+            .await
+            .into_iter()
+            .filter(|st| st.dataset == "MEET_RESULTS")
+            .collect();
+    find_age_rules(pool, &tenant)
+        .await
+        .assign_ages(&mut results);
+    let qualifications = find_qualifications(&sets, &standards, &results);
+
+    let swimmer_qualifications: Vec<SwimmerQualifications> = swimmers
+        .into_iter()
+        .map(|swimmer| SwimmerQualifications {
+            qualifications: qualifications
+                .iter()
+                .filter(|q| q.result.swimmer.id == swimmer.id)
+                .cloned()
+                .collect(),
+            swimmer,
+        })
+        .filter(|sq| !sq.qualifications.is_empty())
+        .collect();
+
+    let mut context = Context::new();
+    context.insert("swimmers", &swimmer_qualifications);
+    context.insert("sets", &all_sets);
+    context.insert("set", &set);
+    context.insert("club", &club);
+    context.insert("home_club", &tenant.club);
+    context.insert("group", &group);
+    context.insert("groups", &groups);
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("qualifications.html", &context)
+                .unwrap(),
+        )
+}
+
+/// Detects the club records broken at the meet, replacing the ones detected by a previous import
+/// of its results.
+pub async fn update_club_records(conn: &PgPool, tenant: &Tenant, meet: &Meet) -> Vec<ClubRecord> {
+    delete_meet_club_records(conn, &tenant.id, &meet.id).await;
+    if tenant.club.is_empty() {
+        return vec![];
+    }
+
+    let records = find_club_records(conn, &tenant.id).await;
+    let mut results = find_club_results(conn, &tenant.id, meet, &tenant.club).await;
+    find_age_rules(conn, tenant).await.assign_ages(&mut results);
+    let new_records = detect_records(&records, &results);
+
+    if let Err(e) = insert_club_records(conn, &tenant.id, &new_records).await {
+        log::error!("Failed saving club records of meet {}: {}", meet.id, e);
+        return vec![];
+    }
+    new_records
+}
+
+/// A current club record with the records it broke, from the newest.
+#[derive(Serialize)]
+struct RecordHistory {
+    record: ClubRecord,
+    history: Vec<ClubRecord>,
+}
+
+pub async fn records_view(
+    tenant: Tenant,
+    query: web::Query<RecordQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let pool = &state.get_ref().pool;
+    let gender = query.gender.clone().unwrap_or_default();
+    let course = query.course.clone().unwrap_or_default();
+    let age_group = query.age_group.clone().unwrap_or_default();
+    let group = parse_group(&query.group);
+    let groups = find_all_training_groups(pool, &tenant.id).await;
+    let age_rules = find_age_rules(pool, &tenant).await;
+
+    // Only records held by current members of the group.
+    let members: Option<Vec<String>> = match group {
+        Some(group) => {
+            let members = find_all_swimmers(pool, &tenant.id, "", Some(group), false).await;
+            Some(members.into_iter().map(|s| s.id).collect())
+        }
+        None => None,
+    };
+
+    let records = find_club_records(pool, &tenant.id).await;
+    let mut boards: Vec<RecordHistory> = current_records(&records)
+        .into_iter()
+        .filter(|r| members.as_ref().is_none_or(|m| m.contains(&r.swimmer)))
+        .filter(|r| gender.is_empty() || r.gender == gender)
+        .filter(|r| course.is_empty() || r.course == course)
+        .filter(|r| age_group.is_empty() || r.age_group == age_group)
+        .map(|record| RecordHistory {
+            history: records
+                .iter()
+                .rev()
+                .filter(|r| r.same_category(record) && r.id != record.id)
+                .cloned()
+                .collect(),
+            record: record.clone(),
+        })
+        .collect();
+
+    // Age groups from the youngest, then open records, with events in the usual order.
+    let age_group_order = |name: &str| match age_rules.groups.iter().position(|g| g.name == name) {
+        Some(position) => position,
+        None if name == OPEN_AGE_GROUP => age_rules.groups.len(),
+        None => age_rules.groups.len() + 1,
+    };
+    let style_order = |style: &str| {
+        [
+            "FREESTYLE",
+            "BACKSTROKE",
+            "BREASTSTROKE",
+            "BUTTERFLY",
+            "MEDLEY",
+        ]
+        .iter()
+        .position(|s| *s == style)
+    };
+    boards.sort_by(|a, b| {
+        let key = |r: &ClubRecord| {
+            (
+                age_group_order(&r.age_group),
+                r.age_group.clone(),
+                r.gender.clone(),
+                r.course.clone(),
+                style_order(&r.style),
+                r.distance,
+            )
+        };
+        key(&a.record).cmp(&key(&b.record))
+    });
+
+    let mut age_groups: Vec<String> = age_rules.groups.iter().map(|g| g.name.clone()).collect();
+    age_groups.push(OPEN_AGE_GROUP.to_string());
+
+    let mut context = Context::new();
+    context.insert("boards", &boards);
+    context.insert("group", &group);
+    context.insert("groups", &groups);
+    context.insert("gender", &gender);
+    context.insert("course", &course);
+    context.insert("courses", &COURSES);
+    context.insert("age_group", &age_group);
+    context.insert("age_groups", &age_groups);
+    context.insert("skipped", &query.skipped.unwrap_or_default());
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("records.html", &context)
+                .unwrap(),
+        )
+}
+
+pub async fn records_delete(
+    tenant: Tenant,
+    actor: Actor,
+    path: web::Path<RecordPath>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let records = find_club_records(&state.get_ref().pool, &tenant.id).await;
+    let record = match records.into_iter().find(|r| r.id == path.record) {
+        Some(record) => record,
+        None => return HttpResponse::NotFound().body("Record not found"),
+    };
+
+    delete_club_record(&state.get_ref().pool, &tenant.id, record.id).await;
+
+    let mut entry = AuditEntry::new(&actor, "DELETE", "club_record", &record.id.to_string());
+    entry.meet = record.meet.clone();
+    entry.swimmer = record.swimmer.clone();
+    entry.set_before(&record);
+    insert_audit(&state.get_ref().pool, &tenant.id, &entry).await;
+
+    HttpResponse::SeeOther()
+        .insert_header((LOCATION, "/records"))
+        .finish()
+}
+
+/// Length of the top lists, unless asked otherwise.
+const DEFAULT_TOP: usize = 10;
+
+pub async fn rankings_view(
+    tenant: Tenant,
+    query: web::Query<RankingQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let pool = &state.get_ref().pool;
+    let seasons = find_all_seasons(pool, &tenant.id).await;
+    let season = select_season(&seasons, &query.season);
+    let gender = query.gender.clone().unwrap_or_default();
+    let course = query.course.clone().unwrap_or_default();
+    let age_group = query.age_group.clone().unwrap_or_default();
+    let top = query.top.unwrap_or(DEFAULT_TOP).clamp(1, 100);
+    let group = parse_group(&query.group);
+    let groups = find_all_training_groups(pool, &tenant.id).await;
+
+    // Rankings of a group rank its current members only.
+    let members: Option<Vec<String>> = match group {
+        Some(group) => {
+            let members = find_all_swimmers(pool, &tenant.id, "", Some(group), false).await;
+            Some(members.into_iter().map(|s| s.id).collect())
+        }
+        None => None,
+    };
+
+    let age_rules = find_age_rules(pool, &tenant).await;
+    let mut age_groups: Vec<String> = age_rules.groups.iter().map(|g| g.name.clone()).collect();
+    age_groups.push(OPEN_AGE_GROUP.to_string());
+
+    let mut swims: Vec<SwimmerTime> =
+        find_club_swims(pool, &tenant.id, &tenant.club, season.as_ref())
+            .await
+            .into_iter()
+            .filter(|st| members.as_ref().is_none_or(|m| m.contains(&st.swimmer.id)))
+            .filter(|st| gender.is_empty() || st.swimmer.gender == gender)
+            .filter(|st| course.is_empty() || st.course == course)
+            .collect();
+    age_rules.assign_ages(&mut swims);
+    find_points(pool, &tenant).await.score_all(&mut swims);
+    let rankings: Vec<_> = rank(&swims, &age_groups, top)
+        .into_iter()
+        .filter(|r| age_group.is_empty() || r.age_group == age_group)
+        .collect();
+
+    let mut context = Context::new();
+    context.insert("rankings", &rankings);
+    context.insert("season", &season);
+    context.insert("seasons", &seasons);
+    context.insert("group", &group);
+    context.insert("groups", &groups);
+    context.insert("gender", &gender);
+    context.insert("course", &course);
+    context.insert("courses", &COURSES);
+    context.insert("age_group", &age_group);
+    context.insert("age_groups", &age_groups);
+    context.insert("top", &top);
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("rankings.html", &context)
+                .unwrap(),
+        )
+}
+
+#[derive(Deserialize)]
+pub struct CompareQuery {
+    pub first: Option<String>,
+    pub second: Option<String>,
+    pub third: Option<String>,
+    /// Narrows the swimmers to pick from.
+    pub group: Option<String>,
+    pub course: Option<String>,
+    pub last: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct HeadToHeadTime {
+    /// Converted from other courses when a course is selected and the swimmer has no time in it.
+    best: Option<ConvertedTime>,
+    recent: Vec<SwimmerTime>,
+    fastest: bool,
+}
+
+#[derive(Serialize)]
+struct HeadToHeadEvent {
+    style: String,
+    distance: i32,
+    course: String,
+    times: Vec<HeadToHeadTime>,
+    svg: String,
+}
+
+/// Recent form shows this many of the latest swims when not asked otherwise.
+const DEFAULT_RECENT_SWIMS: usize = 3;
+
+pub async fn compare_view(
+    tenant: Tenant,
+    query: web::Query<CompareQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let pool = &state.get_ref().pool;
+    let course = query.course.clone().unwrap_or_default();
+    let last = query.last.unwrap_or(DEFAULT_RECENT_SWIMS).clamp(1, 10);
+
+    let mut swimmers: Vec<Swimmer> = vec![];
+    for id in [&query.first, &query.second, &query.third]
+        .into_iter()
+        .flatten()
+    {
+        if swimmers.iter().any(|s| &s.id == id) {
+            continue;
+        }
+        if let Some(swimmer) = find_swimmer(pool, &tenant.id, id).await {
+            swimmers.push(swimmer);
+        }
+    }
+    let ids: Vec<String> = swimmers.iter().map(|s| s.id.clone()).collect();
+
+    let today = Local::now().date_naive();
+    let mut history: Vec<SwimmerTime> = find_swimmers_history(pool, &tenant.id, &ids, today).await;
+    find_age_rules(pool, &tenant)
+        .await
+        .assign_ages(&mut history);
+    let points = find_points(pool, &tenant).await;
+    points.score_all(&mut history);
+    let conversions = CourseConversions::new(find_course_conversions(pool, &tenant.id).await);
+
+    // In a selected course, times swum in other courses are converted to compare the swimmers.
+    let mut events: Vec<(&str, &str, i32)> = history
+        .iter()
+        .filter_map(|st| match course.as_str() {
+            "" => Some((st.course.as_str(), st.style.as_str(), st.distance)),
+            _ => conversions
+                .convert(
+                    &st.swimmer.gender,
+                    &st.style,
+                    st.distance,
+                    st.time,
+                    &st.course,
+                    &course,
+                )
+                .map(|(distance, _)| (course.as_str(), st.style.as_str(), distance)),
+        })
+        .collect();
+    events.sort();
+    events.dedup();
+
+    let events: Vec<HeadToHeadEvent> = events
+        .into_iter()
+        .map(|(event_course, style, distance)| {
+            let series: Vec<Vec<&SwimmerTime>> = swimmers
+                .iter()
+                .map(|swimmer| {
+                    history
+                        .iter()
+                        .filter(|st| st.swimmer.id == swimmer.id)
+                        .filter(|st| {
+                            st.course == event_course
+                                && st.style == style
+                                && st.distance == distance
+                        })
+                        .collect()
+                })
+                .collect();
+            let mut times: Vec<HeadToHeadTime> = swimmers
+                .iter()
+                .zip(&series)
+                .map(|(swimmer, swims)| HeadToHeadTime {
+                    best: conversions.fastest_in(
+                        history
+                            .iter()
+                            .filter(|st| st.swimmer.id == swimmer.id && st.style == style)
+                            .filter(|st| !course.is_empty() || st.course == event_course)
+                            .cloned()
+                            .collect(),
+                        event_course,
+                        distance,
+                    ),
+                    recent: swims
+                        .iter()
+                        .rev()
+                        .filter(|st| st.dataset == "MEET_RESULTS")
+                        .take(last)
+                        .map(|st| (*st).clone())
+                        .collect(),
+                    fastest: false,
+                })
+                .collect();
+            let fastest = times
+                .iter()
+                .filter_map(|t| t.best.as_ref())
+                .map(|ct| ct.time)
+                .min();
+            for time in times.iter_mut() {
+                time.fastest = ids.len() > 1 && time.best.as_ref().map(|ct| ct.time) == fastest;
+            }
+            HeadToHeadEvent {
+                style: style.to_string(),
+                distance,
+                course: event_course.to_string(),
+                svg: comparison_chart(&series),
+                times,
+            }
+        })
+        .collect();
+
+    let group = parse_group(&query.group);
+    let groups = find_all_training_groups(pool, &tenant.id).await;
+    let mut all_swimmers = find_all_swimmers(pool, &tenant.id, "", group, false).await;
+    // Swimmers picked before narrowing the group stay in the lists.
+    for swimmer in swimmers.iter().rev() {
+        if !all_swimmers.iter().any(|s| s.id == swimmer.id) {
+            all_swimmers.insert(0, swimmer.clone());
+        }
+    }
+
+    let mut context = Context::new();
+    context.insert("swimmers", &swimmers);
+    context.insert("all_swimmers", &all_swimmers);
+    context.insert("group", &group);
+    context.insert("groups", &groups);
+    context.insert("events", &events);
+    context.insert("course", &course);
+    context.insert("courses", &COURSES);
+    context.insert("last", &last);
+    context.insert("points_table", points.table_name());
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            state
+                .get_ref()
+                .template
+                .render("compare.html", &context)
+                .unwrap(),
+        )
+}
+
+pub async fn group_dashboard_view(
+    tenant: Tenant,
+    path: web::Path<GroupPath>,
+    query: web::Query<SeasonQuery>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let pool = &state.get_ref().pool;
+    let group = match find_training_group(pool, &tenant.id, path.group).await {
+        Some(group) => group,
+        None => return HttpResponse::NotFound().body("Group not found"),
+    };
+    let seasons = find_all_seasons(pool, &tenant.id).await;
+    let season = select_season(&seasons, &query.season);
+    let today = Local::now().date_naive();
+    let season_period = match &season {
+        Some(season) => Period {
+            start_date: season.start_date,
+            end_date: season.end_date,
+            meet: None,
+        },
+        None => Period {
+            start_date: NaiveDate::MIN,
+            end_date: today,
+            meet: None,
+        },
+    };
+
+    let members = find_group_members(pool, &tenant.id, group.id).await;
+    let mut swimmer_ids: Vec<String> = members.iter().map(|m| m.swimmer.id.clone()).collect();
+    swimmer_ids.sort();
+    swimmer_ids.dedup();
+    let mut history = find_swimmers_history(pool, &tenant.id, &swimmer_ids, today).await;
+    find_age_rules(pool, &tenant)
+        .await
+        .assign_ages(&mut history);
+    let conversions = CourseConversions::new(find_course_conversions(pool, &tenant.id).await);
+
+    // New standards are those of the tracked sets, as on the meet report.
+    let tracked_sets: Vec<StandardSet> = find_all_standard_sets(pool, &tenant.id)
+        .await
+        .into_iter()
+        .filter(|set| set.tracked)
+        .collect();
+    let standards = find_time_standards(pool, &tenant.id, None).await;
+    let results: Vec<SwimmerTime> = history
+        .iter()
+        .filter(|st| st.dataset == "MEET_RESULTS")
+        .cloned()
+        .collect();
+    let qualifications = find_qualifications(&tracked_sets, &standards, &results);
+
+    let latest_meet = match group_swims(&members, &history, &season_period)
+        .into_iter()
+        .max_by_key(|st| (st.time_date, st.meet.start_date))
+    {
+        Some(latest) => Some(find_meet(pool, &latest.meet.id).await),
+        None => None,
+    };
+    let meet_metrics = latest_meet.as_ref().map(|meet| {
+        let period = Period {
+            start_date: meet.start_date,
+            end_date: meet.end_date,
+            meet: Some(meet.id.clone()),
+        };
+        group_metrics(&members, &history, &qualifications, &conversions, &period)
+    });
+    let season_metrics = group_metrics(
+        &members,
+        &history,
+        &qualifications,
+        &conversions,
+        &season_period,
+    );
+
+    let mut context = Context::new();
+    context.insert("group", &group);
+    context.insert("season", &season);
+    context.insert("seasons", &seasons);
+    context.insert("latest_meet", &latest_meet);
+    context.insert("meet_metrics", &meet_metrics);
+    context.insert("season_metrics", &season_metrics);
+    context.insert("num_tracked_sets", &tracked_sets.len());
+
     HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(
             state
                 .get_ref()
                 .template
-                .render("meet_form.html", &context)
+                .render("group_dashboard.html", &context)
                 .unwrap(),
         )
 }
@@ -0,0 +1,323 @@
+use crate::model::{ConvertedTime, CourseConversion, SwimmerTime};
+
+/// Default factors from short to long course metres and from short course yards to short course
+/// metres, as (style, from course, from distance, to course, to distance, factor). Tenants may
+/// override them per gender.
+const DEFAULT_FACTORS: [(&str, &str, i32, &str, i32, f64); 32] = [
+    ("FREESTYLE", "SHORT", 50, "LONG", 50, 1.025),
+    ("FREESTYLE", "SHORT", 100, "LONG", 100, 1.022),
+    ("FREESTYLE", "SHORT", 200, "LONG", 200, 1.02),
+    ("FREESTYLE", "SHORT", 400, "LONG", 400, 1.018),
+    ("FREESTYLE", "SHORT", 800, "LONG", 800, 1.016),
+    ("FREESTYLE", "SHORT", 1500, "LONG", 1500, 1.015),
+    ("BACKSTROKE", "SHORT", 50, "LONG", 50, 1.04),
+    ("BACKSTROKE", "SHORT", 100, "LONG", 100, 1.035),
+    ("BACKSTROKE", "SHORT", 200, "LONG", 200, 1.03),
+    ("BREASTSTROKE", "SHORT", 50, "LONG", 50, 1.025),
+    ("BREASTSTROKE", "SHORT", 100, "LONG", 100, 1.022),
+    ("BREASTSTROKE", "SHORT", 200, "LONG", 200, 1.02),
+    ("BUTTERFLY", "SHORT", 50, "LONG", 50, 1.02),
+    ("BUTTERFLY", "SHORT", 100, "LONG", 100, 1.02),
+    ("BUTTERFLY", "SHORT", 200, "LONG", 200, 1.018),
+    ("MEDLEY", "SHORT", 200, "LONG", 200, 1.03),
+    ("MEDLEY", "SHORT", 400, "LONG", 400, 1.025),
+    ("FREESTYLE", "YARDS", 50, "SHORT", 50, 1.11),
+    ("FREESTYLE", "YARDS", 100, "SHORT", 100, 1.11),
+    ("FREESTYLE", "YARDS", 200, "SHORT", 200, 1.11),
+    ("FREESTYLE", "YARDS", 500, "SHORT", 400, 0.8925),
+    ("FREESTYLE", "YARDS", 1000, "SHORT", 800, 0.8925),
+    ("FREESTYLE", "YARDS", 1650, "SHORT", 1500, 0.995),
+    ("BACKSTROKE", "YARDS", 50, "SHORT", 50, 1.11),
+    ("BACKSTROKE", "YARDS", 100, "SHORT", 100, 1.11),
+    ("BACKSTROKE", "YARDS", 200, "SHORT", 200, 1.11),
+    ("BREASTSTROKE", "YARDS", 100, "SHORT", 100, 1.11),
+    ("BREASTSTROKE", "YARDS", 200, "SHORT", 200, 1.11),
+    ("BUTTERFLY", "YARDS", 100, "SHORT", 100, 1.11),
+    ("BUTTERFLY", "YARDS", 200, "SHORT", 200, 1.11),
+    ("MEDLEY", "YARDS", 200, "SHORT", 200, 1.11),
+    ("MEDLEY", "YARDS", 400, "SHORT", 400, 1.11),
+];
+
+const GENDERS: [&str; 2] = ["FEMALE", "MALE"];
+
+/// Conversions between courses in effect for a tenant: the defaults, replaced by the factors
+/// the tenant customized.
+pub struct CourseConversions {
+    conversions: Vec<CourseConversion>,
+}
+
+impl CourseConversions {
+    pub fn new(custom: Vec<CourseConversion>) -> Self {
+        let mut conversions: Vec<CourseConversion> = GENDERS
+            .iter()
+            .flat_map(|gender| {
+                DEFAULT_FACTORS.iter().map(
+                    |(style, from_course, from_distance, to_course, to_distance, factor)| {
+                        CourseConversion {
+                            gender: gender.to_string(),
+                            style: style.to_string(),
+                            from_course: from_course.to_string(),
+                            from_distance: *from_distance,
+                            to_course: to_course.to_string(),
+                            to_distance: *to_distance,
+                            factor: *factor,
+                            custom: false,
+                        }
+                    },
+                )
+            })
+            .collect();
+
+        for conversion in custom {
+            if let Some(default) = conversions.iter_mut().find(|c| c.same_event(&conversion)) {
+                *default = conversion;
+            }
+        }
+
+        Self { conversions }
+    }
+
+    pub fn all(&self) -> &[CourseConversion] {
+        &self.conversions
+    }
+
+    /// Whether the conversion is one of the known events, so it can be customized.
+    pub fn contains(&self, conversion: &CourseConversion) -> bool {
+        self.conversions.iter().any(|c| c.same_event(conversion))
+    }
+
+    /// Estimates the time in the course, going through short course metres between yards and
+    /// long course metres. Returns the distance and time in the course, or nothing when the event
+    /// has no equivalent there.
+    pub fn convert(
+        &self,
+        gender: &str,
+        style: &str,
+        distance: i32,
+        time: i32,
+        from_course: &str,
+        to_course: &str,
+    ) -> Option<(i32, i32)> {
+        if from_course == to_course {
+            return Some((distance, time));
+        }
+
+        let direct = self.conversions.iter().find_map(|c| {
+            if c.gender != gender || c.style != style {
+                None
+            } else if c.from_course == from_course
+                && c.to_course == to_course
+                && c.from_distance == distance
+            {
+                Some((c.to_distance, (time as f64 * c.factor).round() as i32))
+            } else if c.from_course == to_course
+                && c.to_course == from_course
+                && c.to_distance == distance
+            {
+                Some((c.from_distance, (time as f64 / c.factor).round() as i32))
+            } else {
+                None
+            }
+        });
+
+        match direct {
+            Some(converted) => Some(converted),
+            None if from_course != "SHORT" && to_course != "SHORT" => {
+                let (distance, time) =
+                    self.convert(gender, style, distance, time, from_course, "SHORT")?;
+                self.convert(gender, style, distance, time, "SHORT", to_course)
+            }
+            None => None,
+        }
+    }
+
+    /// The fastest of the times, estimated in the course and distance when swum in other
+    /// courses. Times swum in the course are preferred over converted ones.
+    pub fn fastest_in(
+        &self,
+        times: Vec<SwimmerTime>,
+        course: &str,
+        distance: i32,
+    ) -> Option<ConvertedTime> {
+        let converted: Vec<ConvertedTime> = times
+            .into_iter()
+            .filter_map(|st| {
+                let (to_distance, time) = self.convert(
+                    &st.swimmer.gender,
+                    &st.style,
+                    st.distance,
+                    st.time,
+                    &st.course,
+                    course,
+                )?;
+                (to_distance == distance).then(|| ConvertedTime {
+                    course: course.to_string(),
+                    distance,
+                    time,
+                    converted: st.course != course,
+                    original: st,
+                })
+            })
+            .collect();
+
+        converted
+            .into_iter()
+            .min_by_key(|ct| (ct.converted, ct.time))
+    }
+}
+
+impl CourseConversion {
+    fn same_event(&self, other: &CourseConversion) -> bool {
+        self.gender == other.gender
+            && self.style == other.style
+            && self.from_course == other.from_course
+            && self.from_distance == other.from_distance
+            && self.to_course == other.to_course
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Meet, Swimmer};
+
+    fn swimmer_time(course: &str, distance: i32, time: i32) -> SwimmerTime {
+        let mut swimmer = Swimmer::new("1".to_string(), "Anna".to_string(), "Smith".to_string());
+        swimmer.gender = "FEMALE".to_string();
+        let mut st = SwimmerTime::new(
+            swimmer,
+            Meet::new("1".to_string(), course.to_string()),
+            "MEET_RESULTS".to_string(),
+        );
+        st.style = "FREESTYLE".to_string();
+        st.course = course.to_string();
+        st.distance = distance;
+        st.time = time;
+        st
+    }
+
+    #[test]
+    fn convert_keeps_times_in_the_same_course() {
+        let conversions = CourseConversions::new(vec![]);
+        assert_eq!(
+            conversions.convert("FEMALE", "FREESTYLE", 100, 6000, "LONG", "LONG"),
+            Some((100, 6000))
+        );
+    }
+
+    #[test]
+    fn convert_applies_the_factor_and_its_inverse() {
+        let conversions = CourseConversions::new(vec![]);
+        assert_eq!(
+            conversions.convert("FEMALE", "FREESTYLE", 100, 6000, "SHORT", "LONG"),
+            Some((100, 6132))
+        );
+        assert_eq!(
+            conversions.convert("FEMALE", "FREESTYLE", 100, 6132, "LONG", "SHORT"),
+            Some((100, 6000))
+        );
+    }
+
+    #[test]
+    fn convert_inverts_back_to_the_original_time() {
+        let conversions = CourseConversions::new(vec![]);
+        for (course, distance, time) in [
+            ("SHORT", 200, 13000),
+            ("YARDS", 500, 30000),
+            ("LONG", 1500, 100000),
+        ] {
+            for other in ["SHORT", "LONG", "YARDS"] {
+                let (to_distance, to_time) = conversions
+                    .convert("MALE", "FREESTYLE", distance, time, course, other)
+                    .unwrap();
+                let back =
+                    conversions.convert("MALE", "FREESTYLE", to_distance, to_time, other, course);
+                assert_eq!(
+                    back.map(|(d, _)| d),
+                    Some(distance),
+                    "{} to {}",
+                    course,
+                    other
+                );
+                assert!(
+                    (back.unwrap().1 - time).abs() <= 1,
+                    "{} to {}",
+                    course,
+                    other
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn convert_goes_through_short_course_between_yards_and_long_course() {
+        let conversions = CourseConversions::new(vec![]);
+        let short = conversions.convert("FEMALE", "FREESTYLE", 500, 30000, "YARDS", "SHORT");
+        assert_eq!(short, Some((400, 26775)));
+        assert_eq!(
+            conversions.convert("FEMALE", "FREESTYLE", 500, 30000, "YARDS", "LONG"),
+            conversions.convert("FEMALE", "FREESTYLE", 400, 26775, "SHORT", "LONG")
+        );
+        assert_eq!(
+            conversions.convert("FEMALE", "FREESTYLE", 400, 27257, "LONG", "YARDS"),
+            Some((500, 30000))
+        );
+    }
+
+    #[test]
+    fn convert_finds_nothing_for_events_without_an_equivalent() {
+        let conversions = CourseConversions::new(vec![]);
+        assert_eq!(
+            conversions.convert("FEMALE", "BREASTSTROKE", 50, 3500, "YARDS", "SHORT"),
+            None
+        );
+        assert_eq!(
+            conversions.convert("FEMALE", "BREASTSTROKE", 50, 3500, "YARDS", "LONG"),
+            None
+        );
+    }
+
+    #[test]
+    fn custom_factors_replace_the_defaults_of_their_gender() {
+        let custom = CourseConversion {
+            gender: "FEMALE".to_string(),
+            style: "FREESTYLE".to_string(),
+            from_course: "SHORT".to_string(),
+            from_distance: 100,
+            to_course: "LONG".to_string(),
+            to_distance: 100,
+            factor: 1.05,
+            custom: true,
+        };
+        let conversions = CourseConversions::new(vec![custom]);
+        assert_eq!(
+            conversions.convert("FEMALE", "FREESTYLE", 100, 6000, "SHORT", "LONG"),
+            Some((100, 6300))
+        );
+        assert_eq!(
+            conversions.convert("MALE", "FREESTYLE", 100, 6000, "SHORT", "LONG"),
+            Some((100, 6132))
+        );
+    }
+
+    #[test]
+    fn fastest_in_prefers_times_swum_in_the_course() {
+        let conversions = CourseConversions::new(vec![]);
+        let times = vec![
+            swimmer_time("SHORT", 100, 5800),
+            swimmer_time("LONG", 100, 6100),
+        ];
+        let fastest = conversions.fastest_in(times, "LONG", 100).unwrap();
+        assert!(!fastest.converted);
+        assert_eq!(fastest.time, 6100);
+
+        let times = vec![
+            swimmer_time("SHORT", 100, 5800),
+            swimmer_time("SHORT", 100, 6000),
+        ];
+        let fastest = conversions.fastest_in(times, "LONG", 100).unwrap();
+        assert!(fastest.converted);
+        assert_eq!(fastest.time, 5928);
+        assert_eq!(fastest.original.time, 5800);
+    }
+}
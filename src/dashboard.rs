@@ -0,0 +1,150 @@
+use crate::conversion::CourseConversions;
+use crate::model::{GroupMember, SwimmerTime};
+use crate::qualification::Qualification;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// The days, and optionally the single meet, a dashboard is about.
+pub struct Period {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub meet: Option<String>,
+}
+
+impl Period {
+    fn contains(&self, swim: &SwimmerTime) -> bool {
+        swim.time_date >= self.start_date
+            && swim.time_date <= self.end_date
+            && self.meet.as_ref().is_none_or(|meet| &swim.meet.id == meet)
+    }
+}
+
+/// How a training group swam in a period.
+#[derive(Serialize)]
+pub struct GroupMetrics {
+    pub num_members: usize,
+    pub num_participants: usize,
+    /// Percentage of the members of the group in the period who swam.
+    pub participation: Option<f64>,
+    pub num_swims: usize,
+    pub num_personal_bests: usize,
+    /// Percentage of the swims that were personal bests.
+    pub pb_rate: Option<f64>,
+    pub num_improvements: usize,
+    /// Median of the percentages the results improved on their entry times. Swims slower than
+    /// their entry times count as negative improvements.
+    pub median_improvement: Option<f64>,
+    pub num_standards: usize,
+}
+
+/// Measures the meet results of the members of the group in the period, counting swims only while
+/// the swimmer was in the group. `history` holds every time of the members, entries included,
+/// and `qualifications` the standards first achieved by them.
+pub fn group_metrics(
+    members: &[GroupMember],
+    history: &[SwimmerTime],
+    qualifications: &[Qualification],
+    conversions: &CourseConversions,
+    period: &Period,
+) -> GroupMetrics {
+    let swims = group_swims(members, history, period);
+
+    let num_members = members
+        .iter()
+        .filter(|m| {
+            m.start_date <= period.end_date && m.end_date.is_none_or(|end| end >= period.start_date)
+        })
+        .map(|m| &m.swimmer.id)
+        .collect::<HashSet<_>>()
+        .len();
+    let num_participants = swims
+        .iter()
+        .map(|st| &st.swimmer.id)
+        .collect::<HashSet<_>>()
+        .len();
+
+    let num_personal_bests = swims
+        .iter()
+        .filter(|st| is_personal_best(st, history))
+        .count();
+
+    let mut improvements: Vec<f64> = swims
+        .iter()
+        .filter_map(|st| {
+            let entries: Vec<SwimmerTime> = history
+                .iter()
+                .filter(|e| e.dataset == "MEET_ENTRIES" && e.meet.id == st.meet.id)
+                .filter(|e| e.swimmer.id == st.swimmer.id && e.style == st.style)
+                .cloned()
+                .collect();
+            let entry = conversions.fastest_in(entries, &st.course, st.distance)?;
+            (entry.time > 0).then(|| (entry.time - st.time) as f64 / entry.time as f64 * 100.0)
+        })
+        .collect();
+    improvements.sort_by(f64::total_cmp);
+
+    let num_standards = qualifications
+        .iter()
+        .filter(|q| period.contains(&q.result) && in_group(members, &q.result))
+        .count();
+
+    GroupMetrics {
+        num_members,
+        num_participants,
+        participation: percentage(num_participants, num_members),
+        num_swims: swims.len(),
+        num_personal_bests,
+        pb_rate: percentage(num_personal_bests, swims.len()),
+        num_improvements: improvements.len(),
+        median_improvement: median(&improvements),
+        num_standards,
+    }
+}
+
+/// Meet results of the period swum by members of the group while they were in it.
+pub fn group_swims<'a>(
+    members: &[GroupMember],
+    history: &'a [SwimmerTime],
+    period: &Period,
+) -> Vec<&'a SwimmerTime> {
+    history
+        .iter()
+        .filter(|st| st.dataset == "MEET_RESULTS" && period.contains(st) && in_group(members, st))
+        .collect()
+}
+
+fn in_group(members: &[GroupMember], swim: &SwimmerTime) -> bool {
+    members.iter().any(|m| {
+        m.swimmer.id == swim.swimmer.id
+            && m.start_date <= swim.time_date
+            && m.end_date.is_none_or(|end| swim.time_date <= end)
+    })
+}
+
+/// Personal bests as the best time flags of the repository: no faster time of the swimmer in the
+/// event and course up to the date of the swim. Ties count as bests.
+fn is_personal_best(swim: &SwimmerTime, history: &[SwimmerTime]) -> bool {
+    !history.iter().any(|o| {
+        o.swimmer.id == swim.swimmer.id
+            && o.style == swim.style
+            && o.distance == swim.distance
+            && o.course == swim.course
+            && o.time < swim.time
+            && o.time_date <= swim.time_date
+    })
+}
+
+fn percentage(count: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| count as f64 / total as f64 * 100.0)
+}
+
+/// Median of sorted values.
+fn median(values: &[f64]) -> Option<f64> {
+    let middle = values.len() / 2;
+    match values.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((values[middle - 1] + values[middle]) / 2.0),
+        _ => Some(values[middle]),
+    }
+}
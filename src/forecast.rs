@@ -0,0 +1,260 @@
+use crate::model::{Swimmer, SwimmerTime};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Only the recent results tell the current trend of a swimmer.
+const FORECAST_YEARS: f64 = 3.0;
+
+/// A trend needs more results than the two points of a line.
+const MIN_SWIMS: usize = 3;
+
+/// The band covers about 90% of the outcomes the model expects.
+const BAND_Z: f64 = 1.645;
+
+/// Results never spread less than this fraction of the time, even when they lie on a line.
+const MIN_SPREAD: f64 = 0.005;
+
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Projected time of a swimmer in an event at a date, with the band it most likely falls in.
+#[derive(Serialize, Clone)]
+pub struct Forecast {
+    pub style: String,
+    pub distance: i32,
+    pub course: String,
+    pub target_date: NaiveDate,
+    pub best: i32,
+    pub time: i32,
+    pub low: i32,
+    pub high: i32,
+    pub num_swims: usize,
+}
+
+/// Swimmers improve less every year as they get closer to the age most swimmers peak at, and
+/// not at all after it.
+fn peak_age(gender: &str) -> f64 {
+    match gender {
+        "FEMALE" => 21.0,
+        _ => 23.0,
+    }
+}
+
+/// Forecasts every event and course with meet results of the swimmer. Times must be of the
+/// swimmer, in any order.
+pub fn forecast_all(
+    swimmer: &Swimmer,
+    times: &[SwimmerTime],
+    target_date: NaiveDate,
+) -> Vec<Forecast> {
+    let mut events: Vec<(&str, &str, i32)> = times
+        .iter()
+        .filter(|st| st.dataset == "MEET_RESULTS")
+        .map(|st| (st.course.as_str(), st.style.as_str(), st.distance))
+        .collect();
+    events.sort();
+    events.dedup();
+
+    events
+        .into_iter()
+        .filter_map(|(course, style, distance)| {
+            let event_times: Vec<&SwimmerTime> = times
+                .iter()
+                .filter(|st| st.course == course && st.style == style && st.distance == distance)
+                .collect();
+            forecast(swimmer, &event_times, target_date)
+        })
+        .collect()
+}
+
+/// Fits a line to the logarithm of the recent meet results of one event against the age of the
+/// swimmer, then follows it to the target date, slowing the trend down as the swimmer grows
+/// towards the peak age. Without a birth date, the trend is followed as it is. Returns nothing
+/// when there are too few results.
+pub fn forecast(
+    swimmer: &Swimmer,
+    times: &[&SwimmerTime],
+    target_date: NaiveDate,
+) -> Option<Forecast> {
+    let results: Vec<&&SwimmerTime> = times
+        .iter()
+        .filter(|st| st.dataset == "MEET_RESULTS")
+        .collect();
+    let first = results.first()?;
+    let last_date = results.iter().map(|st| st.time_date).max()?;
+
+    // Age in years at a date, or years since the last result when the age is unknown.
+    let origin = swimmer.birth_date.unwrap_or(last_date);
+    let years = |date: NaiveDate| (date - origin).num_days() as f64 / DAYS_PER_YEAR;
+
+    let recent: Vec<(f64, f64)> = results
+        .iter()
+        .filter(|st| years(last_date) - years(st.time_date) <= FORECAST_YEARS)
+        .filter(|st| st.time > 0)
+        .map(|st| (years(st.time_date), (st.time as f64).ln()))
+        .collect();
+    let n = recent.len();
+    if n < MIN_SWIMS {
+        return None;
+    }
+
+    let mean_x = recent.iter().map(|(x, _)| x).sum::<f64>() / n as f64;
+    let mean_y = recent.iter().map(|(_, y)| y).sum::<f64>() / n as f64;
+    let sxx: f64 = recent.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = recent
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    // Results of a single day tell no trend.
+    let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+    let intercept = mean_y - slope * mean_x;
+
+    let residuals: f64 = recent
+        .iter()
+        .map(|(x, y)| (y - intercept - slope * x).powi(2))
+        .sum();
+    let spread = (residuals / (n - 2) as f64).sqrt().max(MIN_SPREAD);
+
+    let from = years(last_date);
+    let to = years(target_date);
+    let progress = if swimmer.birth_date.is_some() {
+        damped_years(from, to, peak_age(&swimmer.gender))
+    } else {
+        to - from
+    };
+    let projected = intercept + slope * from + slope * progress;
+
+    let leverage = if sxx > 0.0 {
+        (to - mean_x).powi(2) / sxx
+    } else {
+        0.0
+    };
+    let margin = BAND_Z * spread * (1.0 + 1.0 / n as f64 + leverage).sqrt();
+
+    Some(Forecast {
+        style: first.style.clone(),
+        distance: first.distance,
+        course: first.course.clone(),
+        target_date,
+        best: results.iter().map(|st| st.time).min()?,
+        time: projected.exp().round() as i32,
+        low: (projected - margin).exp().round() as i32,
+        high: (projected + margin).exp().round() as i32,
+        num_swims: n,
+    })
+}
+
+/// Years of progress between two ages, each counting less the closer it is to the peak age:
+/// the integral of (peak - age) / (peak - from) from `from` to `to`, nothing after the peak.
+fn damped_years(from: f64, to: f64, peak: f64) -> f64 {
+    if from >= peak {
+        return 0.0;
+    }
+    let to = to.min(peak);
+    let span = peak - from;
+    ((peak - from).powi(2) - (peak - to).powi(2)) / (2.0 * span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Meet;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn swimmer(birth_date: Option<NaiveDate>) -> Swimmer {
+        let mut swimmer = Swimmer::new("1".to_string(), "Anna".to_string(), "Smith".to_string());
+        swimmer.gender = "FEMALE".to_string();
+        swimmer.birth_date = birth_date;
+        swimmer
+    }
+
+    fn result(swimmer: &Swimmer, time_date: NaiveDate, time: i32) -> SwimmerTime {
+        let meet = Meet::new("1".to_string(), "LONG".to_string());
+        let mut st = SwimmerTime::new(swimmer.clone(), meet, "MEET_RESULTS".to_string());
+        st.style = "FREESTYLE".to_string();
+        st.distance = 100;
+        st.course = "LONG".to_string();
+        st.time_date = time_date;
+        st.time = time;
+        st
+    }
+
+    #[test]
+    fn forecast_needs_a_minimum_of_results() {
+        let swimmer = swimmer(None);
+        let times = [
+            result(&swimmer, date(2024, 1, 1), 70_000),
+            result(&swimmer, date(2024, 6, 1), 68_000),
+        ];
+        let times: Vec<&SwimmerTime> = times.iter().collect();
+        assert_eq!(times.len(), MIN_SWIMS - 1);
+        assert!(forecast(&swimmer, &times, date(2025, 1, 1)).is_none());
+    }
+
+    #[test]
+    fn forecast_ignores_entry_times() {
+        let swimmer = swimmer(None);
+        let mut entry = result(&swimmer, date(2024, 3, 1), 69_000);
+        entry.dataset = "MEET_ENTRIES".to_string();
+        let times = [
+            result(&swimmer, date(2024, 1, 1), 70_000),
+            entry,
+            result(&swimmer, date(2024, 6, 1), 68_000),
+        ];
+        let times: Vec<&SwimmerTime> = times.iter().collect();
+        assert!(forecast(&swimmer, &times, date(2025, 1, 1)).is_none());
+    }
+
+    #[test]
+    fn forecast_follows_the_trend_without_a_birth_date() {
+        let swimmer = swimmer(None);
+        let times = [
+            result(&swimmer, date(2023, 1, 1), 72_000),
+            result(&swimmer, date(2023, 7, 1), 70_000),
+            result(&swimmer, date(2024, 1, 1), 68_500),
+            result(&swimmer, date(2024, 7, 1), 67_000),
+        ];
+        let times: Vec<&SwimmerTime> = times.iter().collect();
+        let forecast = forecast(&swimmer, &times, date(2025, 1, 1)).unwrap();
+        assert_eq!(forecast.best, 67_000);
+        assert_eq!(forecast.num_swims, 4);
+        assert!(forecast.time < 67_000);
+        assert!(forecast.low < forecast.time && forecast.time < forecast.high);
+    }
+
+    #[test]
+    fn forecast_stops_improving_after_the_peak_age() {
+        let young = swimmer(Some(date(2010, 1, 1)));
+        let adult = swimmer(Some(date(1990, 1, 1)));
+        let swims = [
+            (date(2023, 1, 1), 72_000),
+            (date(2023, 7, 1), 70_000),
+            (date(2024, 1, 1), 68_000),
+        ];
+
+        let times: Vec<SwimmerTime> = swims.iter().map(|(d, t)| result(&adult, *d, *t)).collect();
+        let times: Vec<&SwimmerTime> = times.iter().collect();
+        let near = forecast(&adult, &times, date(2024, 6, 1)).unwrap();
+        let far = forecast(&adult, &times, date(2026, 1, 1)).unwrap();
+        assert_eq!(near.time, far.time);
+
+        let times: Vec<SwimmerTime> = swims.iter().map(|(d, t)| result(&young, *d, *t)).collect();
+        let times: Vec<&SwimmerTime> = times.iter().collect();
+        let near = forecast(&young, &times, date(2024, 6, 1)).unwrap();
+        let far = forecast(&young, &times, date(2026, 1, 1)).unwrap();
+        assert!(far.time < near.time);
+    }
+
+    #[test]
+    fn damped_years_slow_down_towards_the_peak() {
+        assert_eq!(damped_years(21.0, 25.0, 21.0), 0.0);
+        assert_eq!(damped_years(23.0, 25.0, 21.0), 0.0);
+        assert_eq!(damped_years(11.0, 21.0, 21.0), 5.0);
+        assert_eq!(damped_years(11.0, 31.0, 21.0), 5.0);
+        let first_year = damped_years(11.0, 12.0, 21.0);
+        let last_year = damped_years(11.0, 21.0, 21.0) - damped_years(11.0, 20.0, 21.0);
+        assert!(first_year < 1.0 && first_year > last_year);
+    }
+}
@@ -1,4 +1,14 @@
+pub mod age;
+pub mod chart;
 pub mod config;
 pub mod controller;
+pub mod conversion;
+pub mod dashboard;
+pub mod forecast;
+pub mod matching;
 pub mod model;
+pub mod points;
+pub mod qualification;
+pub mod rankings;
+pub mod records;
 pub mod repository;
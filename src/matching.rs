@@ -0,0 +1,400 @@
+use crate::model::{Swimmer, SwimmerAlias};
+use chrono::Datelike;
+use unicode_normalization::UnicodeNormalization;
+
+/// Matches scoring at least this much are accepted without review, as long as no other
+/// candidate scores close to them.
+pub const AUTO_ACCEPT_SCORE: f64 = 0.92;
+const AUTO_ACCEPT_MARGIN: f64 = 0.05;
+
+/// Candidates scoring less than this are not suggested for review.
+pub const MIN_CANDIDATE_SCORE: f64 = 0.6;
+
+/// Words that start compound last names, like "Van Der Berg" or "De La Cruz".
+const PARTICLES: [&str; 12] = [
+    "da", "de", "del", "della", "der", "di", "du", "la", "le", "st", "van", "von",
+];
+
+pub struct NameQuery {
+    pub full_name: String,
+    pub gender: String,
+    pub birth_year: Option<i32>,
+    pub club: String,
+}
+
+pub struct ScoredSwimmer<'a> {
+    pub swimmer: &'a Swimmer,
+    pub score: f64,
+}
+
+pub enum Resolution<'a> {
+    Accepted(&'a Swimmer),
+    Review(Vec<ScoredSwimmer<'a>>),
+}
+
+/// Lowercases the name, removes accents and punctuation, and treats hyphens as spaces.
+pub fn normalize(name: &str) -> String {
+    name.nfd()
+        .filter(|c| !unicode_normalization::char::is_combining_mark(*c))
+        .map(|c| if c == '-' || c == '_' { ' ' } else { c })
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Splits a full name into first and last name. Names with a comma are in the order
+/// "Last, First". Otherwise, the order is given by `last_name_first`, and compound last names
+/// are recognized by their particles.
+pub fn split_full_name(full_name: &str, last_name_first: bool) -> (String, String) {
+    if let Some((last_name, first_name)) = full_name.split_once(',') {
+        return (first_name.trim().to_string(), last_name.trim().to_string());
+    }
+
+    let words: Vec<&str> = full_name.split_whitespace().collect();
+    if words.len() < 2 {
+        return (full_name.trim().to_string(), String::new());
+    }
+
+    let is_particle = |w: &&str| PARTICLES.contains(&normalize(w).as_str());
+    let split = if last_name_first {
+        words.iter().take_while(|w| is_particle(w)).count() + 1
+    } else {
+        words[1..]
+            .iter()
+            .position(is_particle)
+            .map(|p| p + 1)
+            .unwrap_or(words.len() - 1)
+    };
+    let split = split.min(words.len() - 1);
+
+    if last_name_first {
+        (words[split..].join(" "), words[..split].join(" "))
+    } else {
+        (words[..split].join(" "), words[split..].join(" "))
+    }
+}
+
+/// Scores how likely the swimmer, or one of their aliases, is the person in the query, from 0 to 1.
+pub fn score(query: &NameQuery, swimmer: &Swimmer, aliases: &[&SwimmerAlias]) -> f64 {
+    let query_words: Vec<String> = normalize(&query.full_name)
+        .split(' ')
+        .map(String::from)
+        .collect();
+
+    let mut names = vec![(swimmer.first_name.as_str(), swimmer.last_name.as_str())];
+    names.extend(
+        aliases
+            .iter()
+            .filter(|a| !a.first_name.is_empty() || !a.last_name.is_empty())
+            .map(|a| (a.first_name.as_str(), a.last_name.as_str())),
+    );
+
+    let name_score = names
+        .iter()
+        .map(|(first_name, last_name)| name_score(&query_words, first_name, last_name))
+        .fold(0.0, f64::max);
+
+    let mut score = name_score;
+
+    if !query.gender.is_empty() && !swimmer.gender.is_empty() && query.gender != swimmer.gender {
+        score *= 0.3;
+    }
+
+    if let (Some(year), Some(birth_date)) = (query.birth_year, swimmer.birth_date) {
+        score += match (year - birth_date.year()).abs() {
+            0 => 0.05,
+            1 => 0.0,
+            _ => -0.3,
+        };
+    }
+
+    if !query.club.is_empty() && !swimmer.club.is_empty() {
+        score += if query.club == swimmer.club {
+            0.05
+        } else {
+            -0.1
+        };
+    }
+
+    score.clamp(0.0, 1.0)
+}
+
+/// Accepts the best candidate when it is confident and clearly ahead of the others. Otherwise,
+/// returns the plausible candidates for a coach to review.
+pub fn resolve<'a>(
+    query: &NameQuery,
+    swimmers: &'a [Swimmer],
+    aliases: &[SwimmerAlias],
+) -> Resolution<'a> {
+    let mut scored: Vec<ScoredSwimmer> = swimmers
+        .iter()
+        .map(|swimmer| {
+            let swimmer_aliases: Vec<&SwimmerAlias> =
+                aliases.iter().filter(|a| a.swimmer == swimmer.id).collect();
+            ScoredSwimmer {
+                swimmer,
+                score: score(query, swimmer, &swimmer_aliases),
+            }
+        })
+        .filter(|s| s.score >= MIN_CANDIDATE_SCORE)
+        .collect();
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    match scored.as_slice() {
+        [best] if best.score >= AUTO_ACCEPT_SCORE => Resolution::Accepted(best.swimmer),
+        [best, second, ..]
+            if best.score >= AUTO_ACCEPT_SCORE
+                && best.score - second.score >= AUTO_ACCEPT_MARGIN =>
+        {
+            Resolution::Accepted(best.swimmer)
+        }
+        _ => Resolution::Review(scored),
+    }
+}
+
+/// Compares the words of the query with the words of the name in any order, so middle names
+/// and swapped first and last names don't prevent a match.
+fn name_score(query_words: &[String], first_name: &str, last_name: &str) -> f64 {
+    let first_words: Vec<String> = normalize(first_name).split(' ').map(String::from).collect();
+    let last_words: Vec<String> = normalize(last_name).split(' ').map(String::from).collect();
+    let name_words: Vec<&String> = first_words.iter().chain(last_words.iter()).collect();
+
+    let best_match = |word: &String, others: &mut dyn Iterator<Item = &String>| {
+        others.map(|o| jaro_winkler(word, o)).fold(0.0, f64::max)
+    };
+
+    // Every word of the last name must be there, while only the first word of the first name
+    // counts. Similar first names are often siblings, so they weigh more.
+    let last_score = last_words
+        .iter()
+        .map(|w| best_match(w, &mut query_words.iter()))
+        .sum::<f64>()
+        / last_words.len() as f64;
+    let first_score = best_match(&first_words[0], &mut query_words.iter());
+    let coverage = query_words
+        .iter()
+        .map(|w| best_match(w, &mut name_words.iter().copied()))
+        .sum::<f64>()
+        / query_words.len() as f64;
+
+    last_score * first_score.powi(2) * (0.8 + 0.2 * coverage)
+}
+
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return if a == b { 1.0 } else { 0.0 };
+    }
+    if a == b {
+        return 1.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matches = vec![false; a.len()];
+    let mut b_matches = vec![false; b.len()];
+    let mut matches = 0.0;
+
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matches[j] && b[j] == *ca {
+                a_matches[i] = true;
+                b_matches[j] = true;
+                matches += 1.0;
+                break;
+            }
+        }
+    }
+
+    if matches == 0.0 {
+        return 0.0;
+    }
+
+    let a_matched = a
+        .iter()
+        .zip(&a_matches)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let b_matched = b
+        .iter()
+        .zip(&b_matches)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let transpositions = a_matched.zip(b_matched).filter(|(x, y)| x != y).count() as f64 / 2.0;
+
+    let jaro = (matches / a.len() as f64
+        + matches / b.len() as f64
+        + (matches - transpositions) / matches)
+        / 3.0;
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count() as f64;
+
+    jaro + prefix * 0.1 * (1.0 - jaro)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn swimmer(id: &str, first_name: &str, last_name: &str) -> Swimmer {
+        let mut swimmer = Swimmer::new(
+            id.to_string(),
+            first_name.to_string(),
+            last_name.to_string(),
+        );
+        swimmer.gender = "FEMALE".to_string();
+        swimmer
+    }
+
+    fn query(full_name: &str) -> NameQuery {
+        NameQuery {
+            full_name: full_name.to_string(),
+            gender: "FEMALE".to_string(),
+            birth_year: None,
+            club: String::new(),
+        }
+    }
+
+    fn accepted_id(resolution: Resolution) -> Option<String> {
+        match resolution {
+            Resolution::Accepted(swimmer) => Some(swimmer.id.clone()),
+            Resolution::Review(_) => None,
+        }
+    }
+
+    #[test]
+    fn normalize_removes_accents_punctuation_and_hyphens() {
+        assert_eq!(normalize("  José  Müller-O'Brien "), "jose muller obrien");
+        assert_eq!(normalize("Ève_Lefèvre"), "eve lefevre");
+    }
+
+    #[test]
+    fn split_full_name_reads_last_name_first_with_a_comma() {
+        assert_eq!(
+            split_full_name("Van Der Berg, Anna Maria", false),
+            ("Anna Maria".to_string(), "Van Der Berg".to_string())
+        );
+    }
+
+    #[test]
+    fn split_full_name_keeps_particles_in_the_last_name() {
+        assert_eq!(
+            split_full_name("Anna Van Der Berg", false),
+            ("Anna".to_string(), "Van Der Berg".to_string())
+        );
+        assert_eq!(
+            split_full_name("Van Der Berg Anna", true),
+            ("Anna".to_string(), "Van Der Berg".to_string())
+        );
+        assert_eq!(
+            split_full_name("Anna Maria Smith", false),
+            ("Anna Maria".to_string(), "Smith".to_string())
+        );
+        assert_eq!(
+            split_full_name("Anna", false),
+            ("Anna".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn score_ignores_accents_and_word_order() {
+        let swimmer = swimmer("1", "Zoé", "Lefèvre");
+        assert_eq!(score(&query("Zoe Lefevre"), &swimmer, &[]), 1.0);
+        assert_eq!(score(&query("LEFEVRE, Zoe"), &swimmer, &[]), 1.0);
+    }
+
+    #[test]
+    fn score_matches_aliases() {
+        let swimmer = swimmer("1", "Elizabeth", "Smith");
+        let alias = SwimmerAlias {
+            id: 1,
+            swimmer: "1".to_string(),
+            alias_id: "2".to_string(),
+            first_name: "Liz".to_string(),
+            last_name: "Smith".to_string(),
+        };
+        assert!(score(&query("Liz Smith"), &swimmer, &[]) < AUTO_ACCEPT_SCORE);
+        assert_eq!(score(&query("Liz Smith"), &swimmer, &[&alias]), 1.0);
+    }
+
+    #[test]
+    fn score_penalizes_other_genders_birth_years_and_clubs() {
+        let mut swimmer = swimmer("1", "Anna", "Smith");
+        swimmer.birth_date = NaiveDate::from_ymd_opt(2010, 5, 1);
+        swimmer.club = "ABC".to_string();
+
+        let mut other_gender = query("Anna Smith");
+        other_gender.gender = "MALE".to_string();
+        assert!(score(&other_gender, &swimmer, &[]) < MIN_CANDIDATE_SCORE);
+
+        let mut birth_year = query("Anna Smith");
+        birth_year.birth_year = Some(2011);
+        assert_eq!(score(&birth_year, &swimmer, &[]), 1.0);
+        birth_year.birth_year = Some(2013);
+        assert!(score(&birth_year, &swimmer, &[]) < AUTO_ACCEPT_SCORE);
+
+        let mut other_club = query("Anna Smith");
+        other_club.club = "XYZ".to_string();
+        assert!(score(&other_club, &swimmer, &[]) < AUTO_ACCEPT_SCORE);
+    }
+
+    #[test]
+    fn resolve_accepts_a_confident_match() {
+        let swimmers = vec![
+            swimmer("1", "Anna", "Smith"),
+            swimmer("2", "Hannah", "Jones"),
+        ];
+        let resolution = resolve(&query("Anna Smith"), &swimmers, &[]);
+        assert_eq!(accepted_id(resolution), Some("1".to_string()));
+    }
+
+    #[test]
+    fn resolve_reviews_matches_below_the_accept_score() {
+        let swimmers = vec![swimmer("1", "Anna", "Smith")];
+        let resolution = resolve(&query("Anne Smith"), &swimmers, &[]);
+        let Resolution::Review(candidates) = resolution else {
+            panic!("a similar name must not be accepted");
+        };
+        assert_eq!(candidates.len(), 1);
+        assert!(candidates[0].score >= MIN_CANDIDATE_SCORE);
+        assert!(candidates[0].score < AUTO_ACCEPT_SCORE);
+    }
+
+    #[test]
+    fn resolve_reviews_candidates_within_the_margin() {
+        let mut first = swimmer("1", "Anna", "Smith");
+        first.birth_date = NaiveDate::from_ymd_opt(2010, 1, 1);
+        first.club = "ABC".to_string();
+        let mut second = swimmer("2", "Anna", "Smith");
+        second.birth_date = NaiveDate::from_ymd_opt(2011, 1, 1);
+        second.club = "XYZ".to_string();
+        let swimmers = vec![first, second];
+
+        // The birth year tells them apart by less than the margin.
+        let mut birth_year = query("Anna Smith");
+        birth_year.birth_year = Some(2010);
+        assert_eq!(accepted_id(resolve(&birth_year, &swimmers, &[])), None);
+
+        // The club tells them apart by more than the margin.
+        let mut club = query("Anna Smith");
+        club.club = "ABC".to_string();
+        assert_eq!(
+            accepted_id(resolve(&club, &swimmers, &[])),
+            Some("1".to_string())
+        );
+    }
+
+    #[test]
+    fn resolve_reviews_nobody_without_plausible_candidates() {
+        let swimmers = vec![swimmer("1", "Hannah", "Jones")];
+        let Resolution::Review(candidates) = resolve(&query("Anna Smith"), &swimmers, &[]) else {
+            panic!("an unrelated name must not be accepted");
+        };
+        assert!(candidates.is_empty());
+    }
+}
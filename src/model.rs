@@ -1,3 +1,4 @@
+use actix_web::cookie::Key;
 use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
@@ -6,6 +7,34 @@ use tera::Tera;
 pub struct AppState {
     pub pool: PgPool,
     pub template: Tera,
+    pub tenants: Vec<Tenant>,
+    pub trusted_proxies: Vec<String>,
+    pub cookie_key: Key,
+}
+
+impl AppState {
+    /// Tenants are identified by the host of the request. Unknown hosts belong to no tenant,
+    /// unless the default tenant is the only one.
+    pub fn find_tenant(&self, host: &str) -> Option<&Tenant> {
+        let single_default = match self.tenants.as_slice() {
+            [tenant] if tenant.id == DEFAULT_TENANT => Some(tenant),
+            _ => None,
+        };
+        self.tenants
+            .iter()
+            .find(|t| !t.host.is_empty() && t.host.eq_ignore_ascii_case(host))
+            .or(single_default)
+    }
+}
+
+pub const DEFAULT_TENANT: &str = "default";
+
+#[derive(Serialize, Clone)]
+pub struct Tenant {
+    pub id: String,
+    pub name: String,
+    pub host: String,
+    pub club: String,
 }
 
 #[derive(Serialize, Clone)]
@@ -14,7 +43,9 @@ pub struct Swimmer {
     pub first_name: String,
     pub last_name: String,
     pub gender: String,
-    pub birth_date: NaiveDate,
+    pub birth_date: Option<NaiveDate>,
+    pub club: String,
+    pub active: bool,
 }
 
 impl Swimmer {
@@ -24,12 +55,21 @@ impl Swimmer {
             first_name,
             last_name,
             gender: String::new(),
-            birth_date: NaiveDate::MIN,
+            birth_date: None,
+            club: String::new(),
+            active: true,
         }
     }
+
+    /// Age in full years on the date, when the date of birth is known.
+    pub fn age_on(&self, date: NaiveDate) -> Option<i32> {
+        self.birth_date
+            .and_then(|birth_date| date.years_since(birth_date))
+            .map(|years| years as i32)
+    }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct SwimmerTime {
     pub swimmer: Swimmer,
     pub style: String,
@@ -39,6 +79,44 @@ pub struct SwimmerTime {
     pub time_date: NaiveDate,
     pub meet: Meet,
     pub dataset: String,
+    pub round: String,
+    pub place: Option<i32>,
+    pub heat: Option<i32>,
+    pub lane: Option<i32>,
+    pub reaction_time: Option<i32>,
+    pub personal_best: bool,
+    pub season_best: bool,
+    /// Performance points of the time in the points table of the tenant.
+    pub points: Option<i32>,
+    /// Age of the swimmer at the meet, by the age rule of the tenant.
+    pub age: Option<i32>,
+    /// Name of the age group of the swimmer at the meet. Empty when the age is unknown.
+    pub age_group: String,
+}
+
+impl SwimmerTime {
+    pub fn new(swimmer: Swimmer, meet: Meet, dataset: String) -> Self {
+        Self {
+            swimmer,
+            style: String::new(),
+            distance: 0,
+            course: String::new(),
+            time: 0,
+            time_date: NaiveDate::MAX,
+            meet,
+            dataset,
+            round: String::new(),
+            place: None,
+            heat: None,
+            lane: None,
+            reaction_time: None,
+            personal_best: false,
+            season_best: false,
+            points: None,
+            age: None,
+            age_group: String::new(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -48,8 +126,38 @@ pub struct Meet {
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
     pub course: String,
+    pub location: String,
+    pub pool: String,
+    pub host_club: String,
+    pub sanction: String,
+    pub meet_type: String,
 }
 
+/// Courses of the pool: short course metres, long course metres and short course yards. Times
+/// are only compared within the same course.
+pub const COURSES: [&str; 3] = ["SHORT", "LONG", "YARDS"];
+
+/// Genders as files and forms tell them, stored as FEMALE or MALE.
+pub fn convert_gender(gender: &str) -> Option<&'static str> {
+    match gender.trim().to_uppercase().as_str() {
+        "F" | "W" | "FEMALE" | "GIRLS" | "WOMEN" => Some("FEMALE"),
+        "M" | "MALE" | "BOYS" | "MEN" => Some("MALE"),
+        _ => None,
+    }
+}
+
+pub fn course_name(course: &str) -> &str {
+    match course {
+        "SHORT" => "Short Course",
+        "LONG" => "Long Course",
+        "YARDS" => "Short Course Yards",
+        _ => course,
+    }
+}
+
+/// Meet types, from the most to the least common.
+pub const MEET_TYPES: [&str; 4] = ["INVITATIONAL", "CHAMPIONSHIP", "TIME_TRIAL", "DUAL_MEET"];
+
 impl Meet {
     pub fn new(id: String, course: String) -> Self {
         Self {
@@ -58,10 +166,26 @@ impl Meet {
             start_date: NaiveDate::MIN,
             end_date: NaiveDate::MAX,
             course,
+            location: String::new(),
+            pool: String::new(),
+            host_club: String::new(),
+            sanction: String::new(),
+            meet_type: String::new(),
         }
     }
 }
 
+/// The rows of the tenant that go away with the meet.
+#[derive(Serialize)]
+pub struct MeetDependents {
+    pub num_times: i64,
+    pub num_imports: i64,
+    pub num_reviews: i64,
+    pub num_sessions: i64,
+    /// Other tenants following the meet keep it, with its sessions.
+    pub num_followers: i64,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ImportHistory {
     pub id: i32,
@@ -73,3 +197,345 @@ pub struct ImportHistory {
     pub meet: Meet,
     pub dataset: String,
 }
+
+#[derive(Serialize)]
+pub struct MeetSession {
+    pub id: i32,
+    pub meet: String,
+    pub session_date: NaiveDate,
+    pub period: String,
+    pub events: Vec<MeetEvent>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MeetEvent {
+    pub id: i32,
+    pub session: i32,
+    pub session_date: NaiveDate,
+    pub gender: String,
+    pub style: String,
+    pub distance: i32,
+    pub round: String,
+}
+
+impl MeetEvent {
+    /// An event without gender or round applies to all genders or rounds.
+    pub fn matches(&self, swimmer_time: &SwimmerTime) -> bool {
+        self.style == swimmer_time.style
+            && self.distance == swimmer_time.distance
+            && (self.gender.is_empty()
+                || convert_gender(&self.gender)
+                    .is_some_and(|g| Some(g) == convert_gender(&swimmer_time.swimmer.gender)))
+            && (self.round.is_empty() || self.round == swimmer_time.round)
+    }
+}
+
+#[derive(Serialize)]
+pub struct Club {
+    pub code: String,
+    pub name: String,
+    pub num_swimmers: i64,
+}
+
+#[derive(Serialize)]
+pub struct SwimmerAlias {
+    pub id: i32,
+    pub swimmer: String,
+    pub alias_id: String,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+#[derive(Serialize)]
+pub struct SwimmerMerge {
+    pub id: i32,
+    pub survivor: Swimmer,
+    pub merged: Swimmer,
+    pub num_times: i32,
+    pub num_discarded: i32,
+    pub merge_time: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+pub struct NameReview {
+    pub id: i32,
+    pub meet: Meet,
+    pub full_name: String,
+    pub club: String,
+    pub gender: String,
+    /// Year of birth told by the results, give or take one when they tell the age.
+    pub birth_year: Option<i32>,
+    pub num_times: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Coach {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Serialize)]
+pub struct TrainingGroup {
+    pub id: i32,
+    pub name: String,
+    pub coaches: Vec<Coach>,
+    pub num_swimmers: i64,
+}
+
+#[derive(Serialize)]
+pub struct GroupMember {
+    pub id: i32,
+    pub swimmer: Swimmer,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Season {
+    pub id: i32,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub long_course_start: Option<NaiveDate>,
+}
+
+impl Season {
+    /// The short course and long course phases of the season, or the whole season when it has no
+    /// long course phase.
+    pub fn phases(&self) -> Vec<Season> {
+        match self
+            .long_course_start
+            .and_then(|start| Some((start, start.pred_opt()?)))
+        {
+            Some((start, short_course_end)) => vec![
+                Season {
+                    name: format!("{} Short Course", self.name),
+                    end_date: short_course_end,
+                    long_course_start: None,
+                    ..self.clone()
+                },
+                Season {
+                    name: format!("{} Long Course", self.name),
+                    start_date: start,
+                    long_course_start: None,
+                    ..self.clone()
+                },
+            ],
+            None => vec![self.clone()],
+        }
+    }
+}
+
+/// Whoever is making a change: the coach selected in the browser, the user authenticated by
+/// a trusted proxy in front of the app or, at last, the unverified address of the client.
+#[derive(Serialize, Clone)]
+pub struct Actor {
+    pub coach: Option<i32>,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub coach: Option<i32>,
+    pub actor: String,
+    pub action: String,
+    pub entity: String,
+    pub entity_id: String,
+    pub meet: String,
+    pub swimmer: String,
+    /// Values before and after the change, in JSON. Empty when there was nothing before or after.
+    pub before: String,
+    pub after: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl AuditEntry {
+    pub fn new(actor: &Actor, action: &str, entity: &str, entity_id: &str) -> Self {
+        Self {
+            id: 0,
+            coach: actor.coach,
+            actor: actor.name.clone(),
+            action: action.to_string(),
+            entity: entity.to_string(),
+            entity_id: entity_id.to_string(),
+            meet: String::new(),
+            swimmer: String::new(),
+            before: String::new(),
+            after: String::new(),
+            created_at: NaiveDateTime::MIN,
+        }
+    }
+
+    pub fn set_before<T: Serialize>(&mut self, value: &T) {
+        self.before = serde_json::to_string(value).unwrap_or_default();
+    }
+
+    pub fn set_after<T: Serialize>(&mut self, value: &T) {
+        self.after = serde_json::to_string(value).unwrap_or_default();
+    }
+}
+
+/// Multiplies times of an event in one course to estimate them in another course. Yard events
+/// longer than 200 convert to shorter metre events, like the 500 yards to the 400 metres.
+#[derive(Serialize, Clone)]
+pub struct CourseConversion {
+    pub gender: String,
+    pub style: String,
+    pub from_course: String,
+    pub from_distance: i32,
+    pub to_course: String,
+    pub to_distance: i32,
+    pub factor: f64,
+    /// Set by the tenant instead of the default.
+    pub custom: bool,
+}
+
+/// A time estimated in another course from a time actually swum.
+#[derive(Serialize)]
+pub struct ConvertedTime {
+    pub course: String,
+    pub distance: i32,
+    pub time: i32,
+    pub converted: bool,
+    pub original: SwimmerTime,
+}
+
+/// Standards, like provincial or national cuts, with the window in which times qualify.
+#[derive(Serialize, Clone)]
+pub struct StandardSet {
+    pub id: i32,
+    pub name: String,
+    pub qualifying_start: Option<NaiveDate>,
+    pub qualifying_end: Option<NaiveDate>,
+    pub num_standards: i64,
+    /// Qualifications for the set are tracked and announced after meets.
+    pub tracked: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TimeStandard {
+    pub id: i32,
+    pub standard_set: i32,
+    pub set_name: String,
+    /// Levels tell standards of the same set apart, like "A" and "AA". Often empty.
+    pub level: String,
+    pub gender: String,
+    pub style: String,
+    pub distance: i32,
+    pub course: String,
+    pub min_age: Option<i32>,
+    pub max_age: Option<i32>,
+    pub time: i32,
+}
+
+impl TimeStandard {
+    /// Standards without ages apply to everyone, while swimmers of unknown age only meet those.
+    pub fn applies_to(&self, age: Option<i32>) -> bool {
+        match age {
+            Some(age) => {
+                self.min_age.is_none_or(|min| age >= min)
+                    && self.max_age.is_none_or(|max| age <= max)
+            }
+            None => self.min_age.is_none() && self.max_age.is_none(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self.level.as_str() {
+            "" => self.set_name.clone(),
+            level => format!("{} {}", self.set_name, level),
+        }
+    }
+}
+
+/// Points table loaded by the tenant from a file. Base time tables score times like World
+/// Aquatics points, while score tables give the points of the slowest time reaching them.
+#[derive(Serialize, Clone)]
+pub struct CustomPointsTable {
+    pub id: i32,
+    pub name: String,
+    pub kind: String,
+    /// Used for the points of the tenant instead of World Aquatics points.
+    pub active: bool,
+    pub num_rows: i64,
+}
+
+pub const POINTS_TABLE_KINDS: [&str; 2] = ["BASE_TIME", "SCORE"];
+
+#[derive(Serialize, Clone)]
+pub struct PointsRow {
+    pub gender: String,
+    pub style: String,
+    pub distance: i32,
+    pub course: String,
+    pub min_age: Option<i32>,
+    pub max_age: Option<i32>,
+    pub time: i32,
+    /// Points of the time in score tables. Base times are worth 1000 points.
+    pub points: Option<i32>,
+}
+
+impl PointsRow {
+    pub fn applies_to(&self, age: Option<i32>) -> bool {
+        match age {
+            Some(age) => {
+                self.min_age.is_none_or(|min| age >= min)
+                    && self.max_age.is_none_or(|max| age <= max)
+            }
+            None => self.min_age.is_none() && self.max_age.is_none(),
+        }
+    }
+}
+
+/// Rules to determine the age of swimmers at a meet: their age on the first day of the meet, or
+/// the age they turn in the year of the meet, on December 31.
+pub const AGE_RULES: [&str; 2] = ["MEET_START", "DECEMBER_31"];
+
+/// Band of ages competing together, like 11-12. Bands without a minimum or maximum are open, like
+/// 10 & Under.
+#[derive(Serialize, Clone)]
+pub struct AgeGroup {
+    pub id: i32,
+    pub name: String,
+    pub min_age: Option<i32>,
+    pub max_age: Option<i32>,
+}
+
+impl AgeGroup {
+    pub fn contains(&self, age: i32) -> bool {
+        self.min_age.is_none_or(|min| age >= min) && self.max_age.is_none_or(|max| age <= max)
+    }
+}
+
+/// A club record of an age group, gender, course and event. Seeded records of swimmers and meets
+/// unknown to the application only have their names.
+#[derive(Serialize, Clone)]
+pub struct ClubRecord {
+    pub id: i32,
+    pub age_group: String,
+    pub gender: String,
+    pub style: String,
+    pub distance: i32,
+    pub course: String,
+    pub time: i32,
+    pub swimmer: String,
+    pub swimmer_name: String,
+    pub record_date: NaiveDate,
+    pub meet: String,
+    pub meet_name: String,
+    /// `SEED` for records loaded from a file, `IMPORT` for records detected in meet results.
+    pub source: String,
+}
+
+impl ClubRecord {
+    pub fn same_category(&self, other: &ClubRecord) -> bool {
+        self.age_group == other.age_group
+            && self.gender == other.gender
+            && self.style == other.style
+            && self.distance == other.distance
+            && self.course == other.course
+    }
+}
@@ -0,0 +1,391 @@
+use crate::conversion::CourseConversions;
+use crate::model::{PointsRow, SwimmerTime, COURSES};
+
+/// Scores times, so swims of different events can be compared.
+pub trait PointsTable {
+    fn name(&self) -> &str;
+
+    /// Points of the time, or nothing when the table does not score the event at the age.
+    fn points(
+        &self,
+        gender: &str,
+        style: &str,
+        distance: i32,
+        course: &str,
+        age: Option<i32>,
+        time: i32,
+    ) -> Option<i32>;
+}
+
+/// World Aquatics base times of 2024, as (gender, style, distance, course, time).
+const WORLD_AQUATICS_BASE_TIMES: [(&str, &str, i32, &str, i32); 70] = [
+    ("MALE", "FREESTYLE", 50, "LONG", 20_910),
+    ("MALE", "FREESTYLE", 100, "LONG", 46_860),
+    ("MALE", "FREESTYLE", 200, "LONG", 102_000),
+    ("MALE", "FREESTYLE", 400, "LONG", 220_070),
+    ("MALE", "FREESTYLE", 800, "LONG", 452_120),
+    ("MALE", "FREESTYLE", 1500, "LONG", 871_020),
+    ("MALE", "BACKSTROKE", 50, "LONG", 23_550),
+    ("MALE", "BACKSTROKE", 100, "LONG", 51_600),
+    ("MALE", "BACKSTROKE", 200, "LONG", 111_920),
+    ("MALE", "BREASTSTROKE", 50, "LONG", 25_950),
+    ("MALE", "BREASTSTROKE", 100, "LONG", 56_880),
+    ("MALE", "BREASTSTROKE", 200, "LONG", 125_480),
+    ("MALE", "BUTTERFLY", 50, "LONG", 22_270),
+    ("MALE", "BUTTERFLY", 100, "LONG", 49_450),
+    ("MALE", "BUTTERFLY", 200, "LONG", 110_340),
+    ("MALE", "MEDLEY", 200, "LONG", 114_000),
+    ("MALE", "MEDLEY", 400, "LONG", 242_500),
+    ("FEMALE", "FREESTYLE", 50, "LONG", 23_610),
+    ("FEMALE", "FREESTYLE", 100, "LONG", 51_710),
+    ("FEMALE", "FREESTYLE", 200, "LONG", 112_850),
+    ("FEMALE", "FREESTYLE", 400, "LONG", 235_380),
+    ("FEMALE", "FREESTYLE", 800, "LONG", 484_790),
+    ("FEMALE", "FREESTYLE", 1500, "LONG", 920_480),
+    ("FEMALE", "BACKSTROKE", 50, "LONG", 26_980),
+    ("FEMALE", "BACKSTROKE", 100, "LONG", 57_330),
+    ("FEMALE", "BACKSTROKE", 200, "LONG", 123_140),
+    ("FEMALE", "BREASTSTROKE", 50, "LONG", 29_160),
+    ("FEMALE", "BREASTSTROKE", 100, "LONG", 64_130),
+    ("FEMALE", "BREASTSTROKE", 200, "LONG", 138_950),
+    ("FEMALE", "BUTTERFLY", 50, "LONG", 24_430),
+    ("FEMALE", "BUTTERFLY", 100, "LONG", 55_480),
+    ("FEMALE", "BUTTERFLY", 200, "LONG", 121_810),
+    ("FEMALE", "MEDLEY", 200, "LONG", 126_120),
+    ("FEMALE", "MEDLEY", 400, "LONG", 265_870),
+    ("MALE", "FREESTYLE", 50, "SHORT", 20_160),
+    ("MALE", "FREESTYLE", 100, "SHORT", 44_840),
+    ("MALE", "FREESTYLE", 200, "SHORT", 99_370),
+    ("MALE", "FREESTYLE", 400, "SHORT", 212_250),
+    ("MALE", "FREESTYLE", 800, "SHORT", 443_420),
+    ("MALE", "FREESTYLE", 1500, "SHORT", 846_880),
+    ("MALE", "BACKSTROKE", 50, "SHORT", 22_110),
+    ("MALE", "BACKSTROKE", 100, "SHORT", 48_330),
+    ("MALE", "BACKSTROKE", 200, "SHORT", 105_630),
+    ("MALE", "BREASTSTROKE", 50, "SHORT", 25_250),
+    ("MALE", "BREASTSTROKE", 100, "SHORT", 55_280),
+    ("MALE", "BREASTSTROKE", 200, "SHORT", 120_160),
+    ("MALE", "BUTTERFLY", 50, "SHORT", 21_750),
+    ("MALE", "BUTTERFLY", 100, "SHORT", 47_780),
+    ("MALE", "BUTTERFLY", 200, "SHORT", 108_240),
+    ("MALE", "MEDLEY", 100, "SHORT", 49_280),
+    ("MALE", "MEDLEY", 200, "SHORT", 109_630),
+    ("MALE", "MEDLEY", 400, "SHORT", 234_810),
+    ("FEMALE", "FREESTYLE", 50, "SHORT", 22_930),
+    ("FEMALE", "FREESTYLE", 100, "SHORT", 50_250),
+    ("FEMALE", "FREESTYLE", 200, "SHORT", 110_310),
+    ("FEMALE", "FREESTYLE", 400, "SHORT", 231_300),
+    ("FEMALE", "FREESTYLE", 800, "SHORT", 479_340),
+    ("FEMALE", "FREESTYLE", 1500, "SHORT", 918_010),
+    ("FEMALE", "BACKSTROKE", 50, "SHORT", 25_600),
+    ("FEMALE", "BACKSTROKE", 100, "SHORT", 54_890),
+    ("FEMALE", "BACKSTROKE", 200, "SHORT", 118_940),
+    ("FEMALE", "BREASTSTROKE", 50, "SHORT", 28_560),
+    ("FEMALE", "BREASTSTROKE", 100, "SHORT", 62_360),
+    ("FEMALE", "BREASTSTROKE", 200, "SHORT", 134_570),
+    ("FEMALE", "BUTTERFLY", 50, "SHORT", 24_380),
+    ("FEMALE", "BUTTERFLY", 100, "SHORT", 54_050),
+    ("FEMALE", "BUTTERFLY", 200, "SHORT", 119_320),
+    ("FEMALE", "MEDLEY", 100, "SHORT", 56_510),
+    ("FEMALE", "MEDLEY", 200, "SHORT", 121_860),
+    ("FEMALE", "MEDLEY", 400, "SHORT", 258_940),
+];
+
+/// Base times are worth 1000 points, and points grow with the cube of the speed.
+fn cubic_points(base_time: i32, time: i32) -> Option<i32> {
+    if time <= 0 {
+        return None;
+    }
+    Some((1000.0 * (base_time as f64 / time as f64).powi(3)).floor() as i32)
+}
+
+/// World Aquatics points, the same for every age. There are no base times in yards.
+pub struct WorldAquatics;
+
+impl PointsTable for WorldAquatics {
+    fn name(&self) -> &str {
+        "World Aquatics"
+    }
+
+    fn points(
+        &self,
+        gender: &str,
+        style: &str,
+        distance: i32,
+        course: &str,
+        _: Option<i32>,
+        time: i32,
+    ) -> Option<i32> {
+        WORLD_AQUATICS_BASE_TIMES
+            .iter()
+            .find(|(g, s, d, c, _)| *g == gender && *s == style && *d == distance && *c == course)
+            .and_then(|(_, _, _, _, base_time)| cubic_points(*base_time, time))
+    }
+}
+
+/// Base times loaded from a file, often by age like Rudolph-style tables, scored like World
+/// Aquatics points.
+pub struct BaseTimeTable {
+    pub name: String,
+    pub rows: Vec<PointsRow>,
+}
+
+impl PointsTable for BaseTimeTable {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn points(
+        &self,
+        gender: &str,
+        style: &str,
+        distance: i32,
+        course: &str,
+        age: Option<i32>,
+        time: i32,
+    ) -> Option<i32> {
+        find_rows(&self.rows, gender, style, distance, course, age)
+            .next()
+            .and_then(|row| cubic_points(row.time, time))
+    }
+}
+
+/// Points loaded from a file with the slowest time reaching each score. Times slower than all
+/// of them score no points.
+pub struct ScoreTable {
+    pub name: String,
+    pub rows: Vec<PointsRow>,
+}
+
+impl PointsTable for ScoreTable {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn points(
+        &self,
+        gender: &str,
+        style: &str,
+        distance: i32,
+        course: &str,
+        age: Option<i32>,
+        time: i32,
+    ) -> Option<i32> {
+        let mut rows = find_rows(&self.rows, gender, style, distance, course, age).peekable();
+        rows.peek()?;
+        Some(
+            rows.filter(|row| time <= row.time)
+                .filter_map(|row| row.points)
+                .max()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+fn find_rows<'a>(
+    rows: &'a [PointsRow],
+    gender: &'a str,
+    style: &'a str,
+    distance: i32,
+    course: &'a str,
+    age: Option<i32>,
+) -> impl Iterator<Item = &'a PointsRow> {
+    rows.iter().filter(move |row| {
+        row.gender == gender
+            && row.style == style
+            && row.distance == distance
+            && row.course == course
+            && row.applies_to(age)
+    })
+}
+
+/// Points of the tenant: its points table, with times of events the table does not score
+/// converted to the courses it does.
+pub struct Points {
+    table: Box<dyn PointsTable>,
+    conversions: CourseConversions,
+}
+
+impl Points {
+    pub fn new(table: Box<dyn PointsTable>, conversions: CourseConversions) -> Self {
+        Self { table, conversions }
+    }
+
+    pub fn table_name(&self) -> &str {
+        self.table.name()
+    }
+
+    /// Points of the time, by the age of the swimmer at the meet. Ages must be assigned first for
+    /// tables by age.
+    pub fn score(&self, swimmer_time: &SwimmerTime) -> Option<i32> {
+        let gender = &swimmer_time.swimmer.gender;
+        let style = &swimmer_time.style;
+        let age = swimmer_time.age;
+
+        self.table
+            .points(
+                gender,
+                style,
+                swimmer_time.distance,
+                &swimmer_time.course,
+                age,
+                swimmer_time.time,
+            )
+            .or_else(|| {
+                COURSES
+                    .iter()
+                    .filter(|course| **course != swimmer_time.course)
+                    .find_map(|course| {
+                        let (distance, time) = self.conversions.convert(
+                            gender,
+                            style,
+                            swimmer_time.distance,
+                            swimmer_time.time,
+                            &swimmer_time.course,
+                            course,
+                        )?;
+                        self.table
+                            .points(gender, style, distance, course, age, time)
+                    })
+            })
+    }
+
+    pub fn score_all(&self, times: &mut [SwimmerTime]) {
+        for swimmer_time in times {
+            swimmer_time.points = self.score(swimmer_time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Meet, Swimmer};
+
+    fn row(
+        min_age: Option<i32>,
+        max_age: Option<i32>,
+        time: i32,
+        points: Option<i32>,
+    ) -> PointsRow {
+        PointsRow {
+            gender: "FEMALE".to_string(),
+            style: "FREESTYLE".to_string(),
+            distance: 100,
+            course: "LONG".to_string(),
+            min_age,
+            max_age,
+            time,
+            points,
+        }
+    }
+
+    #[test]
+    fn base_times_score_1000_points() {
+        assert_eq!(
+            WorldAquatics.points("FEMALE", "FREESTYLE", 100, "LONG", None, 51_710),
+            Some(1000)
+        );
+        assert_eq!(
+            WorldAquatics.points("MALE", "MEDLEY", 400, "SHORT", Some(12), 234_810),
+            Some(1000)
+        );
+    }
+
+    #[test]
+    fn points_grow_with_the_cube_of_the_speed() {
+        assert_eq!(
+            WorldAquatics.points("FEMALE", "FREESTYLE", 100, "LONG", None, 103_420),
+            Some(125)
+        );
+        assert_eq!(
+            WorldAquatics.points("FEMALE", "FREESTYLE", 100, "LONG", None, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn world_aquatics_has_no_yards() {
+        assert_eq!(
+            WorldAquatics.points("FEMALE", "FREESTYLE", 100, "YARDS", None, 51_710),
+            None
+        );
+    }
+
+    #[test]
+    fn base_time_tables_score_by_age() {
+        let table = BaseTimeTable {
+            name: "By Age".to_string(),
+            rows: vec![
+                row(Some(11), Some(12), 70_000, None),
+                row(Some(13), Some(14), 60_000, None),
+            ],
+        };
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", Some(12), 70_000),
+            Some(1000)
+        );
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", Some(13), 60_000),
+            Some(1000)
+        );
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", Some(15), 60_000),
+            None
+        );
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", None, 60_000),
+            None
+        );
+    }
+
+    #[test]
+    fn score_tables_give_the_points_of_the_slowest_time_reached() {
+        let table = ScoreTable {
+            name: "Scores".to_string(),
+            rows: vec![
+                row(None, None, 80_000, Some(1)),
+                row(None, None, 70_000, Some(5)),
+                row(None, None, 60_000, Some(10)),
+            ],
+        };
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", None, 75_000),
+            Some(1)
+        );
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", None, 70_000),
+            Some(5)
+        );
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", None, 55_000),
+            Some(10)
+        );
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 100, "LONG", None, 90_000),
+            Some(0)
+        );
+        assert_eq!(
+            table.points("FEMALE", "FREESTYLE", 200, "LONG", None, 70_000),
+            None
+        );
+    }
+
+    #[test]
+    fn times_of_events_without_points_are_converted() {
+        let points = Points::new(Box::new(WorldAquatics), CourseConversions::new(vec![]));
+        let mut swimmer = Swimmer::new("1".to_string(), "Anna".to_string(), "Smith".to_string());
+        swimmer.gender = "FEMALE".to_string();
+        let mut st = SwimmerTime::new(
+            swimmer,
+            Meet::new("1".to_string(), "YARDS".to_string()),
+            "MEET_RESULTS".to_string(),
+        );
+        st.style = "FREESTYLE".to_string();
+        st.distance = 50;
+        st.course = "YARDS".to_string();
+        // 20.658 in yards are 22.930 in short course metres, the base time.
+        st.time = 20_658;
+        assert_eq!(points.score(&st), Some(1000));
+    }
+}
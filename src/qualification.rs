@@ -0,0 +1,51 @@
+use crate::model::{StandardSet, SwimmerTime, TimeStandard};
+
+/// A standard achieved by a swimmer, with the result that first achieved it within the qualifying
+/// window of the standard.
+#[derive(Clone, serde::Serialize)]
+pub struct Qualification {
+    pub standard: TimeStandard,
+    pub result: SwimmerTime,
+}
+
+/// Evaluates meet results against the standards of the sets. Results must be ordered from the
+/// oldest, with the ages of the swimmers assigned.
+pub fn find_qualifications(
+    sets: &[StandardSet],
+    standards: &[TimeStandard],
+    results: &[SwimmerTime],
+) -> Vec<Qualification> {
+    let mut qualifications: Vec<Qualification> = Vec::new();
+
+    for result in results.iter().filter(|st| st.dataset == "MEET_RESULTS") {
+        for standard in standards.iter().filter(|ts| achieves(result, ts)) {
+            let within_window = sets.iter().any(|set| {
+                set.id == standard.standard_set
+                    && set
+                        .qualifying_start
+                        .is_none_or(|start| result.time_date >= start)
+                    && set.qualifying_end.is_none_or(|end| result.time_date <= end)
+            });
+            let achieved = qualifications
+                .iter()
+                .any(|q| q.standard.id == standard.id && q.result.swimmer.id == result.swimmer.id);
+            if within_window && !achieved {
+                qualifications.push(Qualification {
+                    standard: standard.clone(),
+                    result: result.clone(),
+                });
+            }
+        }
+    }
+
+    qualifications
+}
+
+fn achieves(result: &SwimmerTime, standard: &TimeStandard) -> bool {
+    standard.style == result.style
+        && standard.distance == result.distance
+        && standard.course == result.course
+        && standard.gender == result.swimmer.gender
+        && standard.applies_to(result.age)
+        && result.time <= standard.time
+}
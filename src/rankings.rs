@@ -0,0 +1,83 @@
+use crate::model::SwimmerTime;
+use crate::records::OPEN_AGE_GROUP;
+use serde::Serialize;
+
+/// The fastest swimmers of an age group, gender, course and event, each with their best time.
+#[derive(Serialize)]
+pub struct Ranking {
+    pub age_group: String,
+    pub gender: String,
+    pub style: String,
+    pub distance: i32,
+    pub course: String,
+    pub times: Vec<SwimmerTime>,
+}
+
+const STYLE_ORDER: [&str; 5] = [
+    "FREESTYLE",
+    "BACKSTROKE",
+    "BREASTSTROKE",
+    "BUTTERFLY",
+    "MEDLEY",
+];
+
+/// Ranks the swims in the age group of the swimmers at the time and in the open category,
+/// keeping the top of each. Swims must be ordered from the fastest, with the age groups of the
+/// swimmers assigned. Rankings follow the order of the age groups, then the usual event order.
+pub fn rank(swims: &[SwimmerTime], age_groups: &[String], top: usize) -> Vec<Ranking> {
+    let mut rankings: Vec<Ranking> = Vec::new();
+
+    for swim in swims {
+        for age_group in [swim.age_group.as_str(), OPEN_AGE_GROUP]
+            .into_iter()
+            .filter(|ag| !ag.is_empty())
+        {
+            let ranking = match rankings.iter_mut().find(|r| {
+                r.age_group == age_group
+                    && r.gender == swim.swimmer.gender
+                    && r.style == swim.style
+                    && r.distance == swim.distance
+                    && r.course == swim.course
+            }) {
+                Some(ranking) => ranking,
+                None => {
+                    rankings.push(Ranking {
+                        age_group: age_group.to_string(),
+                        gender: swim.swimmer.gender.clone(),
+                        style: swim.style.clone(),
+                        distance: swim.distance,
+                        course: swim.course.clone(),
+                        times: vec![],
+                    });
+                    rankings.last_mut().unwrap()
+                }
+            };
+
+            // Only the best time of each swimmer counts, and swims come from the fastest.
+            let ranked = ranking
+                .times
+                .iter()
+                .any(|st| st.swimmer.id == swim.swimmer.id);
+            if !ranked && ranking.times.len() < top {
+                ranking.times.push(swim.clone());
+            }
+        }
+    }
+
+    let age_group_order = |name: &str| {
+        age_groups
+            .iter()
+            .position(|ag| ag == name)
+            .unwrap_or(age_groups.len())
+    };
+    rankings.sort_by_key(|r| {
+        (
+            age_group_order(&r.age_group),
+            r.gender.clone(),
+            r.course.clone(),
+            STYLE_ORDER.iter().position(|s| *s == r.style),
+            r.distance,
+        )
+    });
+    rankings
+}
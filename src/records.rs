@@ -0,0 +1,59 @@
+use crate::model::{ClubRecord, SwimmerTime};
+
+/// Records of all ages, kept besides the records of each age group.
+pub const OPEN_AGE_GROUP: &str = "Open";
+
+/// The fastest record of each category, the first set when tied.
+pub fn current_records(records: &[ClubRecord]) -> Vec<&ClubRecord> {
+    let mut current: Vec<&ClubRecord> = Vec::new();
+    for record in records {
+        match current.iter_mut().find(|r| r.same_category(record)) {
+            Some(r) if (record.time, record.record_date) < (r.time, r.record_date) => *r = record,
+            Some(_) => {}
+            None => current.push(record),
+        }
+    }
+    current
+}
+
+/// Records broken by the results, in the age group of the swimmer and in the open category.
+/// Results must have the age groups of the swimmers assigned.
+pub fn detect_records(records: &[ClubRecord], results: &[SwimmerTime]) -> Vec<ClubRecord> {
+    let mut results: Vec<&SwimmerTime> = results
+        .iter()
+        .filter(|st| st.dataset == "MEET_RESULTS")
+        .collect();
+    results.sort_by_key(|st| (st.time_date, st.time));
+
+    let mut new_records: Vec<ClubRecord> = Vec::new();
+    for result in results {
+        let age_groups = [result.age_group.as_str(), OPEN_AGE_GROUP];
+        for age_group in age_groups.into_iter().filter(|ag| !ag.is_empty()) {
+            let record = ClubRecord {
+                id: 0,
+                age_group: age_group.to_string(),
+                gender: result.swimmer.gender.clone(),
+                style: result.style.clone(),
+                distance: result.distance,
+                course: result.course.clone(),
+                time: result.time,
+                swimmer: result.swimmer.id.clone(),
+                swimmer_name: format!("{} {}", result.swimmer.first_name, result.swimmer.last_name),
+                record_date: result.time_date,
+                meet: result.meet.id.clone(),
+                meet_name: result.meet.name.clone(),
+                source: "IMPORT".to_string(),
+            };
+            let broken = records
+                .iter()
+                .chain(new_records.iter())
+                .filter(|r| r.same_category(&record))
+                .all(|r| record.time < r.time);
+            if broken {
+                new_records.push(record);
+            }
+        }
+    }
+
+    new_records
+}
@@ -1,166 +1,449 @@
-use crate::model::{ImportHistory, Meet, Swimmer, SwimmerTime};
-use sqlx::postgres::{PgPool, PgRow};
+use crate::matching::split_full_name;
+use crate::model::{
+    AgeGroup, AuditEntry, Club, ClubRecord, Coach, CourseConversion, CustomPointsTable,
+    GroupMember, ImportHistory, Meet, MeetDependents, MeetEvent, MeetSession, NameReview,
+    PointsRow, Season, StandardSet, Swimmer, SwimmerAlias, SwimmerMerge, SwimmerTime, Tenant,
+    TimeStandard, TrainingGroup, DEFAULT_TENANT,
+};
+use chrono::NaiveDate;
+use sqlx::postgres::{PgConnection, PgPool, PgRow};
 use sqlx::Row;
 
-pub async fn find_all_meets(conn: &PgPool) -> Vec<Meet> {
+/// Whether the time `st` was the fastest of its swimmer in the event up to its date, ever and
+/// within its season. Ties count as bests.
+const BEST_TIME_FLAGS: &str = "
+    not exists (select 1 from swimmer_time o
+                where o.tenant = st.tenant and o.swimmer = st.swimmer and o.style = st.style
+                    and o.distance = st.distance and o.course = st.course
+                    and o.official_time < st.official_time and o.date_time <= st.date_time) as personal_best,
+    exists (select 1 from season se
+            where se.tenant = st.tenant and st.date_time between se.start_date and se.end_date
+                and not exists (select 1 from swimmer_time o
+                                where o.tenant = st.tenant and o.swimmer = st.swimmer and o.style = st.style
+                                    and o.distance = st.distance and o.course = st.course
+                                    and o.official_time < st.official_time
+                                    and o.date_time between se.start_date and st.date_time)) as season_best
+";
+
+pub async fn find_all_meets(conn: &PgPool, tenant: &str, season: Option<&Season>) -> Vec<Meet> {
     sqlx::query(
         "
-            select id, name, start_date, end_date, course
-            from meet
-            order by end_date desc
+            select m.id, m.name, m.start_date, m.end_date, m.course,
+                   m.location, m.pool, m.host_club, m.sanction, m.meet_type
+            from meet m
+                join tenant_meet tm on tm.meet = m.id
+            where tm.tenant = $1
+                and ($2::date is null or m.start_date between $2 and $3)
+            order by m.end_date desc
         ",
     )
-    .map(|row: PgRow| Meet {
-        id: row.get("id"),
-        name: row.get("name"),
-        start_date: row.get("start_date"),
-        end_date: row.get("end_date"),
-        course: row.get("course"),
-    })
+    .bind(tenant)
+    .bind(season.map(|s| s.start_date))
+    .bind(season.map(|s| s.end_date))
+    .map(|row: PgRow| meet_from_row(&row))
     .fetch_all(conn)
     .await
     .expect("Failed to fetch meets")
 }
 
 pub async fn find_meet(conn: &PgPool, meet_id: &str) -> Meet {
+    find_optional_meet(conn, meet_id)
+        .await
+        .expect("Failed to fetch meet")
+}
+
+fn meet_from_row(row: &PgRow) -> Meet {
+    Meet {
+        id: row.get("id"),
+        name: row.get("name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        course: row.get::<Option<String>, _>("course").unwrap_or_default(),
+        location: row.get::<Option<String>, _>("location").unwrap_or_default(),
+        pool: row.get::<Option<String>, _>("pool").unwrap_or_default(),
+        host_club: row
+            .get::<Option<String>, _>("host_club")
+            .unwrap_or_default(),
+        sanction: row.get::<Option<String>, _>("sanction").unwrap_or_default(),
+        meet_type: row
+            .get::<Option<String>, _>("meet_type")
+            .unwrap_or_default(),
+    }
+}
+
+pub async fn find_optional_meet(conn: &PgPool, meet_id: &str) -> Option<Meet> {
     sqlx::query(
         "
-            select id, name, start_date, end_date, course
+            select id, name, start_date, end_date, course, location, pool, host_club, sanction, meet_type
             from meet
             where id = $1
         ",
     )
     .bind(meet_id)
-    .map(|row: PgRow| Meet {
-        id: row.get("id"),
-        name: row.get("name"),
-        start_date: row.get("start_date"),
-        end_date: row.get("end_date"),
-        course: row.get("course"),
+    .map(|row: PgRow| meet_from_row(&row))
+    .fetch_optional(conn)
+    .await
+    .expect("Failed to fetch meet")
+}
+
+/// Finds the meet only when the tenant follows it.
+pub async fn find_followed_meet(conn: &PgPool, tenant: &str, meet_id: &str) -> Option<Meet> {
+    sqlx::query(
+        "
+            select m.id, m.name, m.start_date, m.end_date, m.course,
+                   m.location, m.pool, m.host_club, m.sanction, m.meet_type
+            from meet m
+                join tenant_meet tm on tm.meet = m.id
+            where tm.tenant = $1 and m.id = $2
+        ",
+    )
+    .bind(tenant)
+    .bind(meet_id)
+    .map(|row: PgRow| meet_from_row(&row))
+    .fetch_optional(conn)
+    .await
+    .expect("Failed to fetch meet")
+}
+
+pub async fn insert_meet(conn: &PgPool, meet: &Meet) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            insert into meet (id, name, start_date, end_date, course, location, pool, host_club, sanction, meet_type)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ",
+    )
+    .bind(&meet.id)
+    .bind(&meet.name)
+    .bind(meet.start_date)
+    .bind(meet.end_date)
+    .bind(&meet.course)
+    .bind(Some(&meet.location).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.pool).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.host_club).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.sanction).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.meet_type).filter(|v| !v.is_empty()))
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+pub async fn update_meet(conn: &PgPool, meet: &Meet) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            update meet
+            set name = $2, start_date = $3, end_date = $4, course = $5,
+                location = $6, pool = $7, host_club = $8, sanction = $9, meet_type = $10
+            where id = $1
+        ",
+    )
+    .bind(&meet.id)
+    .bind(&meet.name)
+    .bind(meet.start_date)
+    .bind(meet.end_date)
+    .bind(&meet.course)
+    .bind(Some(&meet.location).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.pool).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.host_club).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.sanction).filter(|v| !v.is_empty()))
+    .bind(Some(&meet.meet_type).filter(|v| !v.is_empty()))
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+pub async fn find_meet_dependents(conn: &PgPool, tenant: &str, meet_id: &str) -> MeetDependents {
+    sqlx::query(
+        "
+            select (select count(*) from swimmer_time where tenant = $1 and meet = $2) as num_times,
+                   (select count(*) from import_history where tenant = $1 and meet = $2) as num_imports,
+                   (select count(*) from name_review where tenant = $1 and meet = $2) as num_reviews,
+                   (select count(*) from meet_session where meet = $2) as num_sessions,
+                   (select count(*) from tenant_meet where tenant <> $1 and meet = $2) as num_followers
+        ",
+    )
+    .bind(tenant)
+    .bind(meet_id)
+    .map(|row: PgRow| MeetDependents {
+        num_times: row.get("num_times"),
+        num_imports: row.get("num_imports"),
+        num_reviews: row.get("num_reviews"),
+        num_sessions: row.get("num_sessions"),
+        num_followers: row.get("num_followers"),
     })
     .fetch_one(conn)
     .await
-    .expect("Failed to fetch meet")
+    .expect("Failed to count meet dependents")
+}
+
+/// Removes the times, imports and reviews of the tenant in the meet. Meets are shared, so the
+/// meet and its sessions are only removed when no other tenant follows it.
+pub async fn delete_meet(conn: &PgPool, tenant: &str, meet_id: &str) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query(
+        "
+            delete from name_review_time
+            where review in (select id from name_review where tenant = $1 and meet = $2)
+        ",
+    )
+    .bind(tenant)
+    .bind(meet_id)
+    .execute(&mut *tx)
+    .await?;
+
+    for table in [
+        "name_review",
+        "swimmer_time",
+        "import_history",
+        "club_record",
+        "tenant_meet",
+    ] {
+        sqlx::query(format!("delete from {table} where tenant = $1 and meet = $2").as_str())
+            .bind(tenant)
+            .bind(meet_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let followed = sqlx::query("select 1 from tenant_meet where meet = $1")
+        .bind(meet_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+
+    if !followed {
+        sqlx::query(
+            "delete from meet_event where session in (select id from meet_session where meet = $1)",
+        )
+        .bind(meet_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for sql in [
+            "delete from meet_session where meet = $1",
+            "delete from meet where id = $1",
+        ] {
+            sqlx::query(sql).bind(meet_id).execute(&mut *tx).await?;
+        }
+    }
+
+    tx.commit().await
+}
+
+/// Undoes the imports of a dataset in the meet, removing the times they brought and, for results,
+/// the names still waiting for review. Swimmers created by the imports are kept. Returns the
+/// number of times removed.
+pub async fn rollback_import(
+    conn: &PgPool,
+    tenant: &str,
+    meet_id: &str,
+    dataset: &str,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let removed =
+        sqlx::query("delete from swimmer_time where tenant = $1 and meet = $2 and dataset = $3")
+            .bind(tenant)
+            .bind(meet_id)
+            .bind(dataset)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+    if dataset == "MEET_RESULTS" {
+        sqlx::query(
+            "delete from club_record where tenant = $1 and meet = $2 and source = 'IMPORT'",
+        )
+        .bind(tenant)
+        .bind(meet_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "
+                delete from name_review_time
+                where review in (select id from name_review where tenant = $1 and meet = $2 and status = 'PENDING')
+            ",
+        )
+        .bind(tenant)
+        .bind(meet_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "delete from name_review where tenant = $1 and meet = $2 and status = 'PENDING'",
+        )
+        .bind(tenant)
+        .bind(meet_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("delete from import_history where tenant = $1 and meet = $2 and dataset = $3")
+        .bind(tenant)
+        .bind(meet_id)
+        .bind(dataset)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(removed)
 }
 
-pub async fn find_meets_with_results(conn: &PgPool, except: &str) -> Vec<Meet> {
+pub async fn find_meets_with_results(conn: &PgPool, tenant: &str, except: &str) -> Vec<Meet> {
     sqlx::query(
         "
-            select m.id, m.name, m.start_date, m.end_date, m.course
+            select distinct m.id, m.name, m.start_date, m.end_date, m.course,
+                   m.location, m.pool, m.host_club, m.sanction, m.meet_type
             from meet m
 	            left join import_history ih on m.id = ih.meet
-            where m.id <> $1
+            where ih.tenant = $1
+                and m.id <> $2
                 and ih.dataset = 'MEET_RESULTS'
         ",
     )
+    .bind(tenant)
     .bind(except)
-    .map(|row: PgRow| Meet {
-        id: row.get("id"),
-        name: row.get("name"),
-        start_date: row.get("start_date"),
-        end_date: row.get("end_date"),
-        course: row.get("course"),
-    })
+    .map(|row: PgRow| meet_from_row(&row))
     .fetch_all(conn)
     .await
     .expect("Failed to fetch meets with entries")
 }
 
-pub async fn find_all_swimmers(conn: &PgPool) -> Vec<Swimmer> {
+pub async fn find_all_swimmers(
+    conn: &PgPool,
+    tenant: &str,
+    club: &str,
+    group: Option<i32>,
+    include_inactive: bool,
+) -> Vec<Swimmer> {
     sqlx::query(
         "
-            select id, first_name, last_name, gender, birth_date
-            from swimmer
-            order by first_name, last_name
+            select s.id, s.first_name, s.last_name, s.gender, s.birth_date, s.active,
+                   (select sc.club from swimmer_club sc
+                    where sc.tenant = s.tenant and sc.swimmer = s.id and sc.end_date is null
+                    order by sc.start_date desc limit 1) as club
+            from swimmer s
+            where s.tenant = $1
+                and ($2 = ''
+                    or exists (select 1 from swimmer_club sc
+                               where sc.tenant = s.tenant and sc.swimmer = s.id and sc.club = $2 and sc.end_date is null))
+                and ($3::integer is null
+                    or exists (select 1 from swimmer_group sg
+                               where sg.tenant = s.tenant and sg.swimmer = s.id and sg.training_group = $3
+                                   and sg.end_date is null))
+                and ($4 or s.active)
+            order by s.first_name, s.last_name
         ",
     )
+    .bind(tenant)
+    .bind(club)
+    .bind(group)
+    .bind(include_inactive)
     .map(|row: PgRow| Swimmer {
         id: row.get("id"),
         first_name: row.get("first_name"),
         last_name: row.get("last_name"),
         gender: row.get("gender"),
         birth_date: row.get("birth_date"),
+        club: row.get::<Option<String>, _>("club").unwrap_or_default(),
+        active: row.get("active"),
     })
     .fetch_all(conn)
     .await
     .expect("Failed to fetch swimmers")
 }
 
-pub async fn find_meet_swimmers(conn: &PgPool, import_history: &ImportHistory) -> Vec<SwimmerTime> {
+pub async fn find_meet_swimmers(
+    conn: &PgPool,
+    tenant: &str,
+    import_history: &ImportHistory,
+    round: &str,
+    club: &str,
+    group: Option<i32>,
+) -> Vec<SwimmerTime> {
     let swimmers = import_history
         .swimmers
         .split(',')
         .fold("''".to_string(), |acc, s| format!("{},'{}'", acc, s.trim()));
 
     let sql = format!("
-            select s.id, s.first_name, s.last_name,
-                   st.style, st.distance, st.official_time, st.date_time
+            select s.id, s.first_name, s.last_name, s.gender, s.birth_date,
+                   st.style, st.distance, st.official_time, st.date_time,
+                   st.round, st.place, st.heat, st.lane, st.reaction_time,
+                   {BEST_TIME_FLAGS}
             from swimmer_time st
-                join swimmer s on s.id = st.swimmer
-            where st.meet = $1
+                join swimmer s on s.tenant = st.tenant and s.id = st.swimmer
+            where st.tenant = $6
+                and st.meet = $1
                 and st.dataset = $2
                 and st.course = $3
                 and st.swimmer in ({swimmers})
+                and ($4 = ''
+                    or st.round = $4
+                    or ($4 = 'FINAL' and st.round in ('FINAL_A', 'FINAL_B')))
+                and ($5 = ''
+                    or exists (select 1 from swimmer_club sc
+                               where sc.tenant = s.tenant and sc.swimmer = s.id and sc.club = $5
+                                   and sc.start_date <= st.date_time
+                                   and (sc.end_date is null or sc.end_date >= st.date_time)))
+                and ($7::integer is null
+                    or exists (select 1 from swimmer_group sg
+                               where sg.tenant = s.tenant and sg.swimmer = s.id and sg.training_group = $7
+                                   and sg.start_date <= st.date_time
+                                   and (sg.end_date is null or sg.end_date >= st.date_time)))
             order by s.first_name, s.last_name, st.style, st.distance, st.official_time
         ");
 
     sqlx::query(sql.as_str())
-    .bind(&import_history.meet.id)
-    .bind(&import_history.dataset)
-    .bind(&import_history.meet.course)
-    .map(|row: PgRow| SwimmerTime {
-        swimmer: Swimmer::new(
-            row.get("id"),
-            row.get("first_name"),
-            row.get("last_name")
-        ),
-        style: row.get("style"),
-        distance: row.get("distance"),
-        course: import_history.meet.course.clone(),
-        time: row.get("official_time"),
-        time_date: row.get("date_time"),
-        meet: import_history.meet.clone(),
-        dataset: import_history.dataset.clone(),
-    })
-    .fetch_all(conn)
-    .await
-    .expect("Failed to fetch meet entry swimmers")
-}
-
-pub async fn search_swimmer_by_name(conn: &PgPool, name: String) -> Result<Swimmer, sqlx::Error> {
-    let first_name = name.split(' ').next();
-    let last_name = name.split(' ').nth(1);
-
-    sqlx::query(
-        "
-            select id, first_name, last_name, gender, birth_date
-            from swimmer
-            where first_name = $1 and last_name = $2
-        ",
-    )
-    .bind(first_name)
-    .bind(last_name)
-    .map(|row: PgRow| Swimmer {
-        id: row.get("id"),
-        first_name: first_name.unwrap().trim().to_string(),
-        last_name: last_name.unwrap().trim().to_string(),
-        gender: row.get("gender"),
-        birth_date: row.get("birth_date"),
-    })
-    .fetch_one(conn)
-    .await
+        .bind(&import_history.meet.id)
+        .bind(&import_history.dataset)
+        .bind(&import_history.meet.course)
+        .bind(round)
+        .bind(club)
+        .bind(tenant)
+        .bind(group)
+        .map(|row: PgRow| SwimmerTime {
+            swimmer: Swimmer {
+                gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+                birth_date: row.get("birth_date"),
+                ..Swimmer::new(row.get("id"), row.get("first_name"), row.get("last_name"))
+            },
+            style: row.get("style"),
+            distance: row.get("distance"),
+            course: import_history.meet.course.clone(),
+            time: row.get("official_time"),
+            time_date: row.get("date_time"),
+            meet: import_history.meet.clone(),
+            dataset: import_history.dataset.clone(),
+            round: row.get::<Option<String>, _>("round").unwrap_or_default(),
+            place: row.get("place"),
+            heat: row.get("heat"),
+            lane: row.get("lane"),
+            reaction_time: row.get("reaction_time"),
+            personal_best: row.get("personal_best"),
+            season_best: row.get("season_best"),
+            points: None,
+            age: None,
+            age_group: String::new(),
+        })
+        .fetch_all(conn)
+        .await
+        .expect("Failed to fetch meet entry swimmers")
 }
 
-pub async fn find_import_history(conn: &PgPool, meet_id: &str) -> Vec<ImportHistory> {
+pub async fn find_import_history(conn: &PgPool, tenant: &str, meet_id: &str) -> Vec<ImportHistory> {
     sqlx::query(
         "
-            select ih.id, ih.load_time, ih.num_swimmers, ih.num_entries, ih.duration, ih.swimmers, ih.meet, ih.course, ih.dataset
+            select ih.id, ih.load_time, ih.num_swimmers, ih.num_entries, ih.duration, ih.swimmers, ih.meet, m.course, ih.dataset
             from import_history ih
                 join meet m on m.id = ih.meet
-            where ih.meet = $1
+            where ih.tenant = $1
+                and ih.meet = $2
             order by ih.load_time desc
         ",
     )
+    .bind(tenant)
     .bind(meet_id)
     .map(|row: PgRow| ImportHistory {
         id: row.get("id"),
@@ -177,25 +460,32 @@ pub async fn find_import_history(conn: &PgPool, meet_id: &str) -> Vec<ImportHist
     .expect("Error finding import history")
 }
 
-pub async fn find_latest_imported_swimmers(conn: &PgPool, meet_id: &str) -> Vec<ImportHistory> {
+pub async fn find_latest_imported_swimmers(
+    conn: &PgPool,
+    tenant: &str,
+    meet_id: &str,
+) -> Vec<ImportHistory> {
     sqlx::query(
         "
             select ih.id, ih.load_time, ih.num_swimmers, ih.num_entries, ih.duration, ih.swimmers, ih.meet, m.course, ih.dataset
             from import_history ih
                 join meet m on m.id = ih.meet
-            where ih.meet = $1
+            where ih.tenant = $2
+                and ih.meet = $1
         	    and ih.dataset = 'MEET_ENTRIES'
-        	    and ih.load_time >= (select max(load_time) from import_history where meet = $1 and dataset = 'MEET_ENTRIES')
+        	    and ih.load_time >= (select max(load_time) from import_history where tenant = $2 and meet = $1 and dataset = 'MEET_ENTRIES')
             union
             select ih.id, ih.load_time, ih.num_swimmers, ih.num_entries, ih.duration, ih.swimmers, ih.meet, m.course, ih.dataset
             from import_history ih
                 join meet m on m.id = ih.meet
-            where ih.meet = $1
+            where ih.tenant = $2
+                and ih.meet = $1
         	    and ih.dataset = 'MEET_RESULTS'
-        	    and ih.load_time >= (select max(load_time) from import_history where meet = $1 and dataset = 'MEET_RESULTS')
+        	    and ih.load_time >= (select max(load_time) from import_history where tenant = $2 and meet = $1 and dataset = 'MEET_RESULTS')
         ",
     )
     .bind(meet_id)
+    .bind(tenant)
     .map(|row| ImportHistory {
         id: row.get("id"),
         load_time: row.get("load_time"),
@@ -210,3 +500,2173 @@ pub async fn find_latest_imported_swimmers(conn: &PgPool, meet_id: &str) -> Vec<
     .await
     .expect("Error finding imported swimmers")
 }
+
+pub async fn find_meet_events(conn: &PgPool, meet_id: &str) -> Vec<MeetEvent> {
+    sqlx::query(
+        "
+            select me.id, me.session, ms.session_date, me.gender, me.style, me.distance, me.round
+            from meet_event me
+                join meet_session ms on ms.id = me.session
+            where ms.meet = $1
+            order by ms.session_date, ms.period desc, me.id
+        ",
+    )
+    .bind(meet_id)
+    .map(|row: PgRow| MeetEvent {
+        id: row.get("id"),
+        session: row.get("session"),
+        session_date: row.get("session_date"),
+        gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+        style: row.get("style"),
+        distance: row.get("distance"),
+        round: row.get::<Option<String>, _>("round").unwrap_or_default(),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch meet events")
+}
+
+pub async fn find_meet_sessions(conn: &PgPool, meet_id: &str) -> Vec<MeetSession> {
+    let events = find_meet_events(conn, meet_id).await;
+
+    sqlx::query(
+        "
+            select id, meet, session_date, period
+            from meet_session
+            where meet = $1
+            order by session_date, period desc
+        ",
+    )
+    .bind(meet_id)
+    .map(|row: PgRow| {
+        let id: i32 = row.get("id");
+        MeetSession {
+            id,
+            meet: row.get("meet"),
+            session_date: row.get("session_date"),
+            period: row.get("period"),
+            events: events.iter().filter(|e| e.session == id).cloned().collect(),
+        }
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch meet sessions")
+}
+
+pub async fn insert_meet_session(
+    conn: &PgPool,
+    meet_id: &str,
+    session_date: NaiveDate,
+    period: &str,
+) {
+    sqlx::query(
+        "
+            insert into meet_session (meet, session_date, period)
+            values ($1, $2, $3)
+        ",
+    )
+    .bind(meet_id)
+    .bind(session_date)
+    .bind(period)
+    .execute(conn)
+    .await
+    .expect("Error inserting a meet session");
+}
+
+pub async fn insert_meet_event(conn: &PgPool, event: &MeetEvent) {
+    sqlx::query(
+        "
+            insert into meet_event (session, gender, style, distance, round)
+            values ($1, $2, $3, $4, $5)
+        ",
+    )
+    .bind(event.session)
+    .bind(Some(&event.gender).filter(|g| !g.is_empty()))
+    .bind(&event.style)
+    .bind(event.distance)
+    .bind(Some(&event.round).filter(|r| !r.is_empty()))
+    .execute(conn)
+    .await
+    .expect("Error inserting a meet event");
+}
+
+pub async fn delete_meet_session(conn: &PgPool, meet_id: &str, session_id: i32) {
+    let mut tx = conn.begin().await.expect("Error starting transaction");
+
+    sqlx::query(
+        "
+            delete from meet_event
+            where session in (select id from meet_session where id = $1 and meet = $2)
+        ",
+    )
+    .bind(session_id)
+    .bind(meet_id)
+    .execute(&mut *tx)
+    .await
+    .expect("Error deleting meet events");
+
+    sqlx::query("delete from meet_session where id = $1 and meet = $2")
+        .bind(session_id)
+        .bind(meet_id)
+        .execute(&mut *tx)
+        .await
+        .expect("Error deleting a meet session");
+
+    tx.commit().await.expect("Error committing transaction");
+}
+
+pub async fn find_all_clubs(conn: &PgPool, tenant: &str) -> Vec<Club> {
+    sqlx::query(
+        "
+            select c.code, c.name, count(sc.id) as num_swimmers
+            from club c
+                left join swimmer_club sc on sc.club = c.code and sc.tenant = $1 and sc.end_date is null
+            group by c.code, c.name
+            order by c.name
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| Club {
+        code: row.get("code"),
+        name: row.get("name"),
+        num_swimmers: row.get("num_swimmers"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch clubs")
+}
+
+pub async fn save_club(conn: &PgPool, code: &str, name: &str) {
+    sqlx::query(
+        "
+            insert into club (code, name)
+            values ($1, $2)
+            on conflict (code) do update set name = excluded.name
+        ",
+    )
+    .bind(code)
+    .bind(name)
+    .execute(conn)
+    .await
+    .expect("Error saving a club");
+}
+
+/// Registers the swimmer as a member of the club since the given date, ending the memberships
+/// of other clubs that started before it. Unknown clubs are created using their code as name.
+pub async fn insert_club_membership(
+    conn: &PgPool,
+    tenant: &str,
+    swimmer_id: &str,
+    club: &str,
+    since: NaiveDate,
+) {
+    sqlx::query("insert into club (code, name) values ($1, $1) on conflict do nothing")
+        .bind(club)
+        .execute(conn)
+        .await
+        .expect("Error inserting a club");
+
+    sqlx::query(
+        "
+            update swimmer_club set end_date = $3 - 1
+            where tenant = $4 and swimmer = $1 and club <> $2 and end_date is null and start_date < $3
+        ",
+    )
+    .bind(swimmer_id)
+    .bind(club)
+    .bind(since)
+    .bind(tenant)
+    .execute(conn)
+    .await
+    .expect("Error ending club memberships");
+
+    sqlx::query(
+        "
+            insert into swimmer_club (tenant, swimmer, club, start_date)
+            select $4, $1, $2, $3
+            where not exists (select 1 from swimmer_club
+                              where tenant = $4 and swimmer = $1 and club = $2 and end_date is null)
+            on conflict do nothing
+        ",
+    )
+    .bind(swimmer_id)
+    .bind(club)
+    .bind(since)
+    .bind(tenant)
+    .execute(conn)
+    .await
+    .expect("Error inserting a club membership");
+}
+
+/// Swimmers imported before clubs existed belong to the club of their tenant since their first swim.
+pub async fn adopt_swimmers_without_club(conn: &PgPool, tenant: &str, club: &str) {
+    sqlx::query("insert into club (code, name) values ($1, $1) on conflict do nothing")
+        .bind(club)
+        .execute(conn)
+        .await
+        .expect("Error inserting a club");
+
+    sqlx::query(
+        "
+            insert into swimmer_club (tenant, swimmer, club, start_date)
+            select s.tenant, s.id, $1,
+                   coalesce((select min(st.date_time) from swimmer_time st
+                             where st.tenant = s.tenant and st.swimmer = s.id), current_date)
+            from swimmer s
+            where s.tenant = $2
+                and not exists (select 1 from swimmer_club sc where sc.tenant = s.tenant and sc.swimmer = s.id)
+        ",
+    )
+    .bind(club)
+    .bind(tenant)
+    .execute(conn)
+    .await
+    .expect("Error adopting swimmers without club");
+}
+
+pub async fn find_all_tenants(conn: &PgPool) -> Vec<Tenant> {
+    sqlx::query(
+        "
+            select id, name, host, club
+            from tenant
+            order by id
+        ",
+    )
+    .map(|row: PgRow| Tenant {
+        id: row.get("id"),
+        name: row.get("name"),
+        host: row.get::<Option<String>, _>("host").unwrap_or_default(),
+        club: row.get::<Option<String>, _>("club").unwrap_or_default(),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch tenants")
+}
+
+/// The club of the configuration belongs to the default tenant, unless it already has one.
+pub async fn assign_default_tenant_club(conn: &PgPool, club: &str) {
+    sqlx::query("insert into club (code, name) values ($1, $1) on conflict do nothing")
+        .bind(club)
+        .execute(conn)
+        .await
+        .expect("Error inserting a club");
+
+    sqlx::query("update tenant set club = $1 where id = $2 and club is null")
+        .bind(club)
+        .bind(DEFAULT_TENANT)
+        .execute(conn)
+        .await
+        .expect("Error assigning the club of the default tenant");
+}
+
+pub async fn follow_meet(conn: &PgPool, tenant: &str, meet_id: &str) {
+    sqlx::query(
+        "
+            insert into tenant_meet (tenant, meet)
+            values ($1, $2)
+            on conflict do nothing
+        ",
+    )
+    .bind(tenant)
+    .bind(meet_id)
+    .execute(conn)
+    .await
+    .expect("Error following a meet");
+}
+
+pub async fn find_swimmer(conn: &PgPool, tenant: &str, swimmer_id: &str) -> Option<Swimmer> {
+    sqlx::query(
+        "
+            select s.id, s.first_name, s.last_name, s.gender, s.birth_date, s.active,
+                   (select sc.club from swimmer_club sc
+                    where sc.tenant = s.tenant and sc.swimmer = s.id and sc.end_date is null
+                    order by sc.start_date desc limit 1) as club
+            from swimmer s
+            where s.tenant = $1 and s.id = $2
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmer_id)
+    .map(|row: PgRow| Swimmer {
+        id: row.get("id"),
+        first_name: row.get("first_name"),
+        last_name: row.get("last_name"),
+        gender: row.get("gender"),
+        birth_date: row.get("birth_date"),
+        club: row.get::<Option<String>, _>("club").unwrap_or_default(),
+        active: row.get("active"),
+    })
+    .fetch_optional(conn)
+    .await
+    .expect("Failed to fetch swimmer")
+}
+
+pub async fn update_swimmer(
+    conn: &PgPool,
+    tenant: &str,
+    swimmer: &Swimmer,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            update swimmer set first_name = $3, last_name = $4, gender = $5, birth_date = $6, active = $7
+            where tenant = $1 and id = $2
+        ",
+    )
+    .bind(tenant)
+    .bind(&swimmer.id)
+    .bind(&swimmer.first_name)
+    .bind(&swimmer.last_name)
+    .bind(&swimmer.gender)
+    .bind(swimmer.birth_date)
+    .bind(swimmer.active)
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+/// All times of the swimmer in the season, or ever, from entries and results, with the most
+/// recent first.
+pub async fn find_swimmer_times(
+    conn: &PgPool,
+    tenant: &str,
+    swimmer: &Swimmer,
+    season: Option<&Season>,
+) -> Vec<SwimmerTime> {
+    let sql = format!(
+        "
+            select st.style, st.distance, st.course, st.official_time, st.date_time, st.dataset,
+                   st.round, st.place, st.heat, st.lane, st.reaction_time,
+                   m.id as meet_id, m.name as meet_name, m.start_date, m.end_date, m.course as meet_course,
+                   {BEST_TIME_FLAGS}
+            from swimmer_time st
+                join meet m on m.id = st.meet
+            where st.tenant = $1 and st.swimmer = $2
+                and ($3::date is null or st.date_time between $3 and $4)
+            order by st.date_time, st.style, st.distance, st.official_time
+        "
+    );
+
+    sqlx::query(sql.as_str())
+        .bind(tenant)
+        .bind(&swimmer.id)
+        .bind(season.map(|s| s.start_date))
+        .bind(season.map(|s| s.end_date))
+        .map(|row: PgRow| swimmer_time_from_row(&row, swimmer))
+        .fetch_all(conn)
+        .await
+        .expect("Failed to fetch swimmer times")
+}
+
+/// The fastest time of the swimmer in each event and course. Within a season, these are the
+/// season bests. Otherwise, the personal bests.
+pub async fn find_best_times(
+    conn: &PgPool,
+    tenant: &str,
+    swimmer: &Swimmer,
+    season: Option<&Season>,
+) -> Vec<SwimmerTime> {
+    let sql = format!(
+        "
+            select distinct on (st.course, st.style, st.distance)
+                   st.style, st.distance, st.course, st.official_time, st.date_time, st.dataset,
+                   st.round, st.place, st.heat, st.lane, st.reaction_time,
+                   m.id as meet_id, m.name as meet_name, m.start_date, m.end_date, m.course as meet_course,
+                   {BEST_TIME_FLAGS}
+            from swimmer_time st
+                join meet m on m.id = st.meet
+            where st.tenant = $1 and st.swimmer = $2
+                and ($3::date is null or st.date_time between $3 and $4)
+            order by st.course, st.style, st.distance, st.official_time, st.date_time
+        "
+    );
+
+    sqlx::query(sql.as_str())
+        .bind(tenant)
+        .bind(&swimmer.id)
+        .bind(season.map(|s| s.start_date))
+        .bind(season.map(|s| s.end_date))
+        .map(|row: PgRow| swimmer_time_from_row(&row, swimmer))
+        .fetch_all(conn)
+        .await
+        .expect("Failed to fetch best times")
+}
+
+fn swimmer_time_from_row(row: &PgRow, swimmer: &Swimmer) -> SwimmerTime {
+    SwimmerTime {
+        swimmer: swimmer.clone(),
+        style: row.get("style"),
+        distance: row.get("distance"),
+        course: row.get("course"),
+        time: row.get("official_time"),
+        time_date: row.get("date_time"),
+        meet: Meet {
+            name: row.get("meet_name"),
+            start_date: row.get("start_date"),
+            end_date: row.get("end_date"),
+            ..Meet::new(
+                row.get("meet_id"),
+                row.get::<Option<String>, _>("meet_course")
+                    .unwrap_or_default(),
+            )
+        },
+        dataset: row.get("dataset"),
+        round: row.get::<Option<String>, _>("round").unwrap_or_default(),
+        place: row.get("place"),
+        heat: row.get("heat"),
+        lane: row.get("lane"),
+        reaction_time: row.get("reaction_time"),
+        personal_best: row.get("personal_best"),
+        season_best: row.get("season_best"),
+        points: None,
+        age: None,
+        age_group: String::new(),
+    }
+}
+
+pub async fn find_swimmer_aliases(
+    conn: &PgPool,
+    tenant: &str,
+    swimmer_id: &str,
+) -> Vec<SwimmerAlias> {
+    sqlx::query(
+        "
+            select id, swimmer, alias_id, first_name, last_name
+            from swimmer_alias
+            where tenant = $1 and swimmer = $2
+            order by id
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmer_id)
+    .map(|row: PgRow| SwimmerAlias {
+        id: row.get("id"),
+        swimmer: row.get("swimmer"),
+        alias_id: row.get::<Option<String>, _>("alias_id").unwrap_or_default(),
+        first_name: row
+            .get::<Option<String>, _>("first_name")
+            .unwrap_or_default(),
+        last_name: row
+            .get::<Option<String>, _>("last_name")
+            .unwrap_or_default(),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch swimmer aliases")
+}
+
+pub async fn insert_swimmer_alias(
+    conn: &PgPool,
+    tenant: &str,
+    alias: &SwimmerAlias,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            insert into swimmer_alias (tenant, swimmer, alias_id, first_name, last_name)
+            values ($1, $2, $3, $4, $5)
+        ",
+    )
+    .bind(tenant)
+    .bind(&alias.swimmer)
+    .bind(Some(&alias.alias_id).filter(|a| !a.is_empty()))
+    .bind(Some(&alias.first_name).filter(|n| !n.is_empty()))
+    .bind(Some(&alias.last_name).filter(|n| !n.is_empty()))
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+pub async fn delete_swimmer_alias(conn: &PgPool, tenant: &str, swimmer_id: &str, alias_id: i32) {
+    sqlx::query("delete from swimmer_alias where tenant = $1 and swimmer = $2 and id = $3")
+        .bind(tenant)
+        .bind(swimmer_id)
+        .bind(alias_id)
+        .execute(conn)
+        .await
+        .expect("Error deleting a swimmer alias");
+}
+
+/// Returns the id of the swimmer known by the given id, which may be an alias of a merged swimmer.
+pub async fn resolve_swimmer_id(conn: &PgPool, tenant: &str, swimmer_id: &str) -> String {
+    sqlx::query("select swimmer from swimmer_alias where tenant = $1 and alias_id = $2")
+        .bind(tenant)
+        .bind(swimmer_id)
+        .map(|row: PgRow| row.get("swimmer"))
+        .fetch_optional(conn)
+        .await
+        .expect("Failed to resolve swimmer alias")
+        .unwrap_or_else(|| swimmer_id.to_string())
+}
+
+/// Moves the history of the merged swimmer to the survivor and keeps the id and name of the merged
+/// swimmer as aliases of the survivor. Times both swimmers have are kept only once.
+pub async fn merge_swimmers(
+    conn: &PgPool,
+    tenant: &str,
+    survivor: &Swimmer,
+    merged: &Swimmer,
+) -> Result<(), sqlx::Error> {
+    let same_swim = "
+        s.tenant = d.tenant and s.style = d.style and s.distance = d.distance and s.course = d.course
+        and s.official_time = d.official_time and s.date_time is not distinct from d.date_time
+        and s.dataset is not distinct from d.dataset and coalesce(s.round, '') = coalesce(d.round, '')
+    ";
+
+    let mut tx = conn.begin().await?;
+
+    sqlx::query(
+        format!(
+            "
+                update swimmer_time s
+                set place = coalesce(s.place, d.place),
+                    heat = coalesce(s.heat, d.heat),
+                    lane = coalesce(s.lane, d.lane),
+                    reaction_time = coalesce(s.reaction_time, d.reaction_time)
+                from swimmer_time d
+                where s.tenant = $1 and s.swimmer = $2 and d.swimmer = $3 and {same_swim}
+            "
+        )
+        .as_str(),
+    )
+    .bind(tenant)
+    .bind(&survivor.id)
+    .bind(&merged.id)
+    .execute(&mut *tx)
+    .await?;
+
+    let num_discarded = sqlx::query(
+        format!(
+            "
+                delete from swimmer_time d
+                where d.tenant = $1 and d.swimmer = $3
+                    and exists (select 1 from swimmer_time s where s.swimmer = $2 and {same_swim})
+            "
+        )
+        .as_str(),
+    )
+    .bind(tenant)
+    .bind(&survivor.id)
+    .bind(&merged.id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let num_times =
+        sqlx::query("update swimmer_time set swimmer = $2 where tenant = $1 and swimmer = $3")
+            .bind(tenant)
+            .bind(&survivor.id)
+            .bind(&merged.id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+    sqlx::query(
+        "
+            delete from swimmer_club d
+            where d.tenant = $1 and d.swimmer = $3
+                and exists (select 1 from swimmer_club s
+                            where s.tenant = d.tenant and s.swimmer = $2
+                                and s.club = d.club and s.start_date = d.start_date)
+        ",
+    )
+    .bind(tenant)
+    .bind(&survivor.id)
+    .bind(&merged.id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "
+            delete from swimmer_group d
+            where d.tenant = $1 and d.swimmer = $3
+                and exists (select 1 from swimmer_group s
+                            where s.tenant = d.tenant and s.swimmer = $2
+                                and s.training_group = d.training_group and s.start_date = d.start_date)
+        ",
+    )
+    .bind(tenant)
+    .bind(&survivor.id)
+    .bind(&merged.id)
+    .execute(&mut *tx)
+    .await?;
+
+    for table in [
+        "swimmer_club",
+        "swimmer_group",
+        "swimmer_alias",
+        "name_review",
+        "club_record",
+    ] {
+        sqlx::query(
+            format!("update {table} set swimmer = $2 where tenant = $1 and swimmer = $3").as_str(),
+        )
+        .bind(tenant)
+        .bind(&survivor.id)
+        .bind(&merged.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "
+            insert into swimmer_alias (tenant, swimmer, alias_id, first_name, last_name)
+            values ($1, $2, $3, $4, $5)
+            on conflict do nothing
+        ",
+    )
+    .bind(tenant)
+    .bind(&survivor.id)
+    .bind(&merged.id)
+    .bind(&merged.first_name)
+    .bind(&merged.last_name)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "
+            update import_history
+            set swimmers = array_to_string(array(
+                select distinct case when trim(s) = $3 then $2 else trim(s) end
+                from unnest(string_to_array(swimmers, ',')) s), ', ')
+            where tenant = $1 and $3 = any(select trim(s) from unnest(string_to_array(swimmers, ',')) s)
+        ",
+    )
+    .bind(tenant)
+    .bind(&survivor.id)
+    .bind(&merged.id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("delete from swimmer where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(&merged.id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "
+            insert into swimmer_merge (tenant, survivor, merged, merged_first_name, merged_last_name,
+                                       merged_gender, merged_birth_date, num_times, num_discarded)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ",
+    )
+    .bind(tenant)
+    .bind(&survivor.id)
+    .bind(&merged.id)
+    .bind(&merged.first_name)
+    .bind(&merged.last_name)
+    .bind(&merged.gender)
+    .bind(merged.birth_date)
+    .bind(num_times as i32)
+    .bind(num_discarded as i32)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+pub async fn find_swimmer_merges(conn: &PgPool, tenant: &str) -> Vec<SwimmerMerge> {
+    sqlx::query(
+        "
+            select sm.id, sm.survivor, s.first_name, s.last_name, sm.merged, sm.merged_first_name,
+                   sm.merged_last_name, sm.num_times, sm.num_discarded, sm.merge_time
+            from swimmer_merge sm
+                left join swimmer s on s.tenant = sm.tenant and s.id = sm.survivor
+            where sm.tenant = $1
+            order by sm.merge_time desc
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| SwimmerMerge {
+        id: row.get("id"),
+        survivor: Swimmer::new(
+            row.get("survivor"),
+            row.get::<Option<String>, _>("first_name")
+                .unwrap_or_default(),
+            row.get::<Option<String>, _>("last_name")
+                .unwrap_or_default(),
+        ),
+        merged: Swimmer::new(
+            row.get("merged"),
+            row.get("merged_first_name"),
+            row.get("merged_last_name"),
+        ),
+        num_times: row.get("num_times"),
+        num_discarded: row.get("num_discarded"),
+        merge_time: row.get("merge_time"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch swimmer merges")
+}
+
+pub async fn insert_swimmer(
+    conn: &PgPool,
+    tenant: &str,
+    swimmer: &Swimmer,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            insert into swimmer (tenant, id, first_name, last_name, gender, birth_date)
+            values ($1, $2, $3, $4, $5, $6)
+            on conflict do nothing
+        ",
+    )
+    .bind(tenant)
+    .bind(&swimmer.id)
+    .bind(&swimmer.first_name)
+    .bind(&swimmer.last_name)
+    .bind(&swimmer.gender)
+    .bind(swimmer.birth_date)
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+/// Suffixes keep the ids within the 32 characters of the column: derived ids are cut to 28.
+const MAX_SWIMMER_ID_ATTEMPTS: u32 = 99;
+
+/// Inserts the swimmer under its id, or under the id followed by -2, -3 and so on when taken, so
+/// different swimmers whose derived ids collide are kept apart. Returns the id the swimmer got, or
+/// an error once all the suffixes up to -99 are taken.
+pub async fn insert_swimmer_with_free_id(
+    conn: &PgPool,
+    tenant: &str,
+    swimmer: &Swimmer,
+) -> Result<String, sqlx::Error> {
+    for attempt in 1..=MAX_SWIMMER_ID_ATTEMPTS {
+        let id = match attempt {
+            1 => swimmer.id.clone(),
+            n => format!("{}-{}", swimmer.id, n),
+        };
+        let inserted = sqlx::query(
+            "
+                insert into swimmer (tenant, id, first_name, last_name, gender, birth_date)
+                values ($1, $2, $3, $4, $5, $6)
+                on conflict do nothing
+            ",
+        )
+        .bind(tenant)
+        .bind(&id)
+        .bind(&swimmer.first_name)
+        .bind(&swimmer.last_name)
+        .bind(&swimmer.gender)
+        .bind(swimmer.birth_date)
+        .execute(conn)
+        .await?
+        .rows_affected();
+        if inserted > 0 {
+            return Ok(id);
+        }
+    }
+    Err(sqlx::Error::Protocol(format!(
+        "No free id left for swimmer {}",
+        swimmer.id
+    )))
+}
+
+pub async fn find_all_swimmer_aliases(conn: &PgPool, tenant: &str) -> Vec<SwimmerAlias> {
+    sqlx::query(
+        "
+            select id, swimmer, alias_id, first_name, last_name
+            from swimmer_alias
+            where tenant = $1
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| SwimmerAlias {
+        id: row.get("id"),
+        swimmer: row.get("swimmer"),
+        alias_id: row.get::<Option<String>, _>("alias_id").unwrap_or_default(),
+        first_name: row
+            .get::<Option<String>, _>("first_name")
+            .unwrap_or_default(),
+        last_name: row
+            .get::<Option<String>, _>("last_name")
+            .unwrap_or_default(),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch swimmer aliases")
+}
+
+/// Puts the name in the review queue of the meet, unless it's already there. Returns the id of
+/// the review, its status and the swimmer it was resolved to, if any.
+pub async fn queue_name_review(
+    conn: &PgPool,
+    tenant: &str,
+    meet_id: &str,
+    full_name: &str,
+    club: &str,
+    gender: &str,
+    birth_year: Option<i32>,
+) -> (i32, String, Option<String>) {
+    sqlx::query(
+        "
+            insert into name_review (tenant, meet, full_name, club, gender, birth_year)
+            values ($1, $2, $3, $4, $5, $6)
+            on conflict (tenant, meet, full_name, coalesce(club, ''))
+                do update set gender = excluded.gender,
+                              birth_year = coalesce(excluded.birth_year, name_review.birth_year)
+            returning id, status, swimmer
+        ",
+    )
+    .bind(tenant)
+    .bind(meet_id)
+    .bind(full_name)
+    .bind(Some(club).filter(|c| !c.is_empty()))
+    .bind(Some(gender).filter(|g| !g.is_empty()))
+    .bind(birth_year)
+    .map(|row: PgRow| (row.get("id"), row.get("status"), row.get("swimmer")))
+    .fetch_one(conn)
+    .await
+    .expect("Error queuing a name review")
+}
+
+pub async fn insert_name_review_time(conn: &PgPool, review_id: i32, swimmer_time: &SwimmerTime) {
+    sqlx::query(
+        "
+            insert into name_review_time (review, style, distance, course, official_time, date_time, dataset,
+                                          round, place, heat, lane, reaction_time)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            on conflict do nothing
+        ",
+    )
+    .bind(review_id)
+    .bind(&swimmer_time.style)
+    .bind(swimmer_time.distance)
+    .bind(&swimmer_time.course)
+    .bind(swimmer_time.time)
+    .bind(swimmer_time.time_date)
+    .bind(&swimmer_time.dataset)
+    .bind(Some(&swimmer_time.round).filter(|r| !r.is_empty()))
+    .bind(swimmer_time.place)
+    .bind(swimmer_time.heat)
+    .bind(swimmer_time.lane)
+    .bind(swimmer_time.reaction_time)
+    .execute(conn)
+    .await
+    .expect("Error inserting a time under review");
+}
+
+pub async fn find_pending_name_reviews(conn: &PgPool, tenant: &str) -> Vec<NameReview> {
+    find_name_reviews(conn, tenant, None).await
+}
+
+pub async fn find_name_review(conn: &PgPool, tenant: &str, review_id: i32) -> Option<NameReview> {
+    find_name_reviews(conn, tenant, Some(review_id)).await.pop()
+}
+
+async fn find_name_reviews(conn: &PgPool, tenant: &str, review_id: Option<i32>) -> Vec<NameReview> {
+    sqlx::query(
+        "
+            select nr.id, nr.meet, m.name as meet_name, nr.full_name, nr.club, nr.gender, nr.birth_year, nr.created_at,
+                   (select count(*) from name_review_time nrt where nrt.review = nr.id) as num_times
+            from name_review nr
+                join meet m on m.id = nr.meet
+            where nr.tenant = $1
+                and nr.status = 'PENDING'
+                and ($2::integer is null or nr.id = $2)
+            order by nr.created_at, nr.full_name
+        ",
+    )
+    .bind(tenant)
+    .bind(review_id)
+    .map(|row: PgRow| {
+        let mut meet = Meet::new(row.get("meet"), String::new());
+        meet.name = row.get("meet_name");
+        NameReview {
+            id: row.get("id"),
+            meet,
+            full_name: row.get("full_name"),
+            club: row.get::<Option<String>, _>("club").unwrap_or_default(),
+            gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+            birth_year: row.get("birth_year"),
+            num_times: row.get("num_times"),
+            created_at: row.get("created_at"),
+        }
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch name reviews")
+}
+
+/// Imports the times waiting in the review to the swimmer, and keeps the reviewed name as an
+/// alias of the swimmer, so the next import recognizes it.
+pub async fn resolve_name_review(
+    conn: &PgPool,
+    tenant: &str,
+    review: &NameReview,
+    swimmer_id: &str,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query(
+        "
+            insert into swimmer_time (tenant, swimmer, meet, style, distance, course, official_time, date_time,
+                                      dataset, round, place, heat, lane, reaction_time)
+            select $1, $2, $3, style, distance, course, official_time, date_time,
+                   dataset, round, place, heat, lane, reaction_time
+            from name_review_time
+            where review = $4
+            on conflict do nothing
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmer_id)
+    .bind(&review.meet.id)
+    .bind(review.id)
+    .execute(&mut *tx)
+    .await?;
+
+    let (first_name, last_name) = split_full_name(&review.full_name, false);
+    sqlx::query(
+        "
+            insert into swimmer_alias (tenant, swimmer, first_name, last_name)
+            select $1, $2, $3, $4
+            where not exists (select 1 from swimmer where tenant = $1 and id = $2 and first_name = $3 and last_name = $4)
+                and not exists (select 1 from swimmer_alias
+                                where tenant = $1 and swimmer = $2 and first_name = $3 and last_name = $4)
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmer_id)
+    .bind(first_name)
+    .bind(last_name)
+    .execute(&mut *tx)
+    .await?;
+
+    // The meet report lists the swimmers of the latest import of results.
+    sqlx::query(
+        "
+            update import_history
+            set swimmers = case when swimmers = '' then $3 else swimmers || ', ' || $3 end,
+                num_swimmers = num_swimmers + 1
+            where id = (select max(id) from import_history where tenant = $1 and meet = $2 and dataset = 'MEET_RESULTS')
+                and not $3 = any(select trim(s) from unnest(string_to_array(swimmers, ',')) s)
+        ",
+    )
+    .bind(tenant)
+    .bind(&review.meet.id)
+    .bind(swimmer_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "
+            update name_review set status = $3, swimmer = $4, resolved_at = current_timestamp
+            where tenant = $1 and id = $2
+        ",
+    )
+    .bind(tenant)
+    .bind(review.id)
+    .bind(status)
+    .bind(swimmer_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+pub async fn ignore_name_review(conn: &PgPool, tenant: &str, review_id: i32) {
+    sqlx::query(
+        "
+            update name_review set status = 'IGNORED', resolved_at = current_timestamp
+            where tenant = $1 and id = $2
+        ",
+    )
+    .bind(tenant)
+    .bind(review_id)
+    .execute(conn)
+    .await
+    .expect("Error ignoring a name review");
+}
+
+pub async fn find_all_coaches(conn: &PgPool, tenant: &str) -> Vec<Coach> {
+    sqlx::query(
+        "
+            select id, name, email
+            from coach
+            where tenant = $1
+            order by name
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| Coach {
+        id: row.get("id"),
+        name: row.get("name"),
+        email: row.get::<Option<String>, _>("email").unwrap_or_default(),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch coaches")
+}
+
+pub async fn find_coach(conn: &PgPool, tenant: &str, coach_id: i32) -> Option<Coach> {
+    sqlx::query("select id, name, email from coach where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(coach_id)
+        .map(|row: PgRow| Coach {
+            id: row.get("id"),
+            name: row.get("name"),
+            email: row.get::<Option<String>, _>("email").unwrap_or_default(),
+        })
+        .fetch_optional(conn)
+        .await
+        .expect("Failed to fetch a coach")
+}
+
+pub async fn insert_coach(
+    conn: &PgPool,
+    tenant: &str,
+    name: &str,
+    email: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("insert into coach (tenant, name, email) values ($1, $2, $3)")
+        .bind(tenant)
+        .bind(name)
+        .bind(Some(email).filter(|e| !e.is_empty()))
+        .execute(conn)
+        .await
+        .map(|_| ())
+}
+
+pub async fn find_all_training_groups(conn: &PgPool, tenant: &str) -> Vec<TrainingGroup> {
+    find_training_groups(conn, tenant, None).await
+}
+
+pub async fn find_training_group(
+    conn: &PgPool,
+    tenant: &str,
+    group_id: i32,
+) -> Option<TrainingGroup> {
+    find_training_groups(conn, tenant, Some(group_id))
+        .await
+        .pop()
+}
+
+async fn find_training_groups(
+    conn: &PgPool,
+    tenant: &str,
+    group_id: Option<i32>,
+) -> Vec<TrainingGroup> {
+    let coaches: Vec<(i32, Coach)> = sqlx::query(
+        "
+            select tgc.training_group, c.id, c.name, c.email
+            from training_group_coach tgc
+                join coach c on c.id = tgc.coach
+            where c.tenant = $1
+            order by c.name
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| {
+        let coach = Coach {
+            id: row.get("id"),
+            name: row.get("name"),
+            email: row.get::<Option<String>, _>("email").unwrap_or_default(),
+        };
+        (row.get("training_group"), coach)
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch group coaches");
+
+    sqlx::query(
+        "
+            select tg.id, tg.name, count(sg.id) as num_swimmers
+            from training_group tg
+                left join swimmer_group sg
+                    on sg.tenant = tg.tenant and sg.training_group = tg.id and sg.end_date is null
+            where tg.tenant = $1
+                and ($2::integer is null or tg.id = $2)
+            group by tg.id, tg.name
+            order by tg.name
+        ",
+    )
+    .bind(tenant)
+    .bind(group_id)
+    .map(|row: PgRow| {
+        let id: i32 = row.get("id");
+        TrainingGroup {
+            id,
+            name: row.get("name"),
+            coaches: coaches
+                .iter()
+                .filter(|(g, _)| *g == id)
+                .map(|(_, c)| c.clone())
+                .collect(),
+            num_swimmers: row.get("num_swimmers"),
+        }
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch training groups")
+}
+
+pub async fn insert_training_group(
+    conn: &PgPool,
+    tenant: &str,
+    name: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("insert into training_group (tenant, name) values ($1, $2)")
+        .bind(tenant)
+        .bind(name)
+        .execute(conn)
+        .await
+        .map(|_| ())
+}
+
+pub async fn assign_group_coach(conn: &PgPool, tenant: &str, group_id: i32, coach_id: i32) {
+    sqlx::query(
+        "
+            insert into training_group_coach (training_group, coach)
+            select tg.id, c.id
+            from training_group tg, coach c
+            where tg.tenant = $1 and tg.id = $2 and c.tenant = $1 and c.id = $3
+            on conflict do nothing
+        ",
+    )
+    .bind(tenant)
+    .bind(group_id)
+    .bind(coach_id)
+    .execute(conn)
+    .await
+    .expect("Error assigning a coach");
+}
+
+pub async fn unassign_group_coach(conn: &PgPool, tenant: &str, group_id: i32, coach_id: i32) {
+    sqlx::query(
+        "
+            delete from training_group_coach
+            where training_group = (select id from training_group where tenant = $1 and id = $2)
+                and coach = $3
+        ",
+    )
+    .bind(tenant)
+    .bind(group_id)
+    .bind(coach_id)
+    .execute(conn)
+    .await
+    .expect("Error unassigning a coach");
+}
+
+/// Current members come first, followed by the former members of the group.
+pub async fn find_group_members(conn: &PgPool, tenant: &str, group_id: i32) -> Vec<GroupMember> {
+    sqlx::query(
+        "
+            select sg.id, sg.start_date, sg.end_date,
+                   s.id as swimmer, s.first_name, s.last_name, s.gender, s.birth_date, s.active
+            from swimmer_group sg
+                join swimmer s on s.tenant = sg.tenant and s.id = sg.swimmer
+            where sg.tenant = $1 and sg.training_group = $2
+            order by sg.end_date desc nulls first, s.first_name, s.last_name
+        ",
+    )
+    .bind(tenant)
+    .bind(group_id)
+    .map(|row: PgRow| {
+        let mut swimmer = Swimmer::new(
+            row.get("swimmer"),
+            row.get("first_name"),
+            row.get("last_name"),
+        );
+        swimmer.gender = row.get("gender");
+        swimmer.birth_date = row.get("birth_date");
+        swimmer.active = row.get("active");
+        GroupMember {
+            id: row.get("id"),
+            swimmer,
+            start_date: row.get("start_date"),
+            end_date: row.get("end_date"),
+        }
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch group members")
+}
+
+/// Swimmers train in one group at a time, so joining a group ends the current membership in
+/// other groups.
+pub async fn insert_group_member(
+    conn: &PgPool,
+    tenant: &str,
+    group_id: i32,
+    swimmer_id: &str,
+    since: NaiveDate,
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query(
+        "
+            update swimmer_group set end_date = $4 - 1
+            where tenant = $1 and swimmer = $2 and training_group <> $3 and end_date is null and start_date < $4
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmer_id)
+    .bind(group_id)
+    .bind(since)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "
+            insert into swimmer_group (tenant, swimmer, training_group, start_date)
+            select $1, $2, $3, $4
+            where exists (select 1 from training_group where tenant = $1 and id = $3)
+                and not exists (select 1 from swimmer_group
+                                where tenant = $1 and swimmer = $2 and training_group = $3 and end_date is null)
+            on conflict do nothing
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmer_id)
+    .bind(group_id)
+    .bind(since)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+pub async fn end_group_member(
+    conn: &PgPool,
+    tenant: &str,
+    group_id: i32,
+    member_id: i32,
+    until: NaiveDate,
+) {
+    sqlx::query(
+        "
+            update swimmer_group set end_date = greatest(start_date, $4)
+            where tenant = $1 and training_group = $2 and id = $3 and end_date is null
+        ",
+    )
+    .bind(tenant)
+    .bind(group_id)
+    .bind(member_id)
+    .bind(until)
+    .execute(conn)
+    .await
+    .expect("Error ending a group membership");
+}
+
+pub async fn find_all_seasons(conn: &PgPool, tenant: &str) -> Vec<Season> {
+    sqlx::query(
+        "
+            select id, name, start_date, end_date, long_course_start
+            from season
+            where tenant = $1
+            order by start_date desc
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| Season {
+        id: row.get("id"),
+        name: row.get("name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        long_course_start: row.get("long_course_start"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch seasons")
+}
+
+pub async fn insert_season(
+    conn: &PgPool,
+    tenant: &str,
+    season: &Season,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            insert into season (tenant, name, start_date, end_date, long_course_start)
+            values ($1, $2, $3, $4, $5)
+        ",
+    )
+    .bind(tenant)
+    .bind(&season.name)
+    .bind(season.start_date)
+    .bind(season.end_date)
+    .bind(season.long_course_start)
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+pub async fn delete_season(conn: &PgPool, tenant: &str, season_id: i32) {
+    sqlx::query("delete from season where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(season_id)
+        .execute(conn)
+        .await
+        .expect("Error deleting a season");
+}
+
+pub async fn insert_audit(conn: &PgPool, tenant: &str, entry: &AuditEntry) {
+    sqlx::query(
+        "
+            insert into audit (tenant, coach, actor, action, entity, entity_id, meet, swimmer, before, after)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9::jsonb, $10::jsonb)
+        ",
+    )
+    .bind(tenant)
+    .bind(entry.coach)
+    .bind(&entry.actor)
+    .bind(&entry.action)
+    .bind(&entry.entity)
+    .bind(&entry.entity_id)
+    .bind(Some(&entry.meet).filter(|v| !v.is_empty()))
+    .bind(Some(&entry.swimmer).filter(|v| !v.is_empty()))
+    .bind(Some(&entry.before).filter(|v| !v.is_empty()))
+    .bind(Some(&entry.after).filter(|v| !v.is_empty()))
+    .execute(conn)
+    .await
+    .expect("Error inserting an audit entry");
+}
+
+/// The latest audit entries of the tenant, optionally about a meet or a swimmer.
+pub async fn find_audit_entries(
+    conn: &PgPool,
+    tenant: &str,
+    meet_id: Option<&str>,
+    swimmer_id: Option<&str>,
+    limit: i64,
+) -> Vec<AuditEntry> {
+    sqlx::query(
+        "
+            select id, coach, actor, action, entity, entity_id, meet, swimmer,
+                   before::text as before, after::text as after, created_at
+            from audit
+            where tenant = $1
+                and ($2::varchar is null or meet = $2)
+                and ($3::varchar is null or swimmer = $3)
+            order by created_at desc, id desc
+            limit $4
+        ",
+    )
+    .bind(tenant)
+    .bind(meet_id)
+    .bind(swimmer_id)
+    .bind(limit)
+    .map(|row: PgRow| AuditEntry {
+        id: row.get("id"),
+        coach: row.get("coach"),
+        actor: row.get("actor"),
+        action: row.get("action"),
+        entity: row.get("entity"),
+        entity_id: row.get("entity_id"),
+        meet: row.get::<Option<String>, _>("meet").unwrap_or_default(),
+        swimmer: row.get::<Option<String>, _>("swimmer").unwrap_or_default(),
+        before: row.get::<Option<String>, _>("before").unwrap_or_default(),
+        after: row.get::<Option<String>, _>("after").unwrap_or_default(),
+        created_at: row.get("created_at"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch audit entries")
+}
+
+pub async fn find_course_conversions(conn: &PgPool, tenant: &str) -> Vec<CourseConversion> {
+    sqlx::query(
+        "
+            select gender, style, from_course, from_distance, to_course, to_distance, factor
+            from course_conversion
+            where tenant = $1
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| CourseConversion {
+        gender: row.get("gender"),
+        style: row.get("style"),
+        from_course: row.get("from_course"),
+        from_distance: row.get("from_distance"),
+        to_course: row.get("to_course"),
+        to_distance: row.get("to_distance"),
+        factor: row.get("factor"),
+        custom: true,
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch course conversions")
+}
+
+pub async fn save_course_conversion(conn: &PgPool, tenant: &str, conversion: &CourseConversion) {
+    sqlx::query(
+        "
+            insert into course_conversion (tenant, gender, style, from_course, from_distance, to_course, to_distance, factor)
+            values ($1, $2, $3, $4, $5, $6, $7, $8)
+            on conflict (tenant, gender, style, from_course, from_distance, to_course)
+            do update set to_distance = excluded.to_distance, factor = excluded.factor
+        ",
+    )
+    .bind(tenant)
+    .bind(&conversion.gender)
+    .bind(&conversion.style)
+    .bind(&conversion.from_course)
+    .bind(conversion.from_distance)
+    .bind(&conversion.to_course)
+    .bind(conversion.to_distance)
+    .bind(conversion.factor)
+    .execute(conn)
+    .await
+    .expect("Error saving a course conversion");
+}
+
+pub async fn delete_course_conversion(conn: &PgPool, tenant: &str, conversion: &CourseConversion) {
+    sqlx::query(
+        "
+            delete from course_conversion
+            where tenant = $1 and gender = $2 and style = $3 and from_course = $4 and from_distance = $5
+                and to_course = $6
+        ",
+    )
+    .bind(tenant)
+    .bind(&conversion.gender)
+    .bind(&conversion.style)
+    .bind(&conversion.from_course)
+    .bind(conversion.from_distance)
+    .bind(&conversion.to_course)
+    .execute(conn)
+    .await
+    .expect("Error deleting a course conversion");
+}
+
+/// The fastest time of each swimmer per event and course, to compare with the results of a meet.
+/// Times can be limited to a meet, to a dataset and to those swum before a date.
+pub async fn find_comparison_times(
+    conn: &PgPool,
+    tenant: &str,
+    swimmers: &[String],
+    meet_id: Option<&str>,
+    dataset: Option<&str>,
+    before: Option<NaiveDate>,
+) -> Vec<SwimmerTime> {
+    sqlx::query(
+        "
+            select distinct on (st.swimmer, st.course, st.style, st.distance)
+                   s.id, s.first_name, s.last_name, s.gender,
+                   st.style, st.distance, st.course, st.official_time, st.date_time, st.dataset, st.meet
+            from swimmer_time st
+                join swimmer s on s.tenant = st.tenant and s.id = st.swimmer
+            where st.tenant = $1
+                and st.swimmer = any($2)
+                and ($3::varchar is null or st.meet = $3)
+                and ($4::varchar is null or st.dataset = $4)
+                and ($5::date is null or st.date_time < $5)
+            order by st.swimmer, st.course, st.style, st.distance, st.official_time
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmers)
+    .bind(meet_id)
+    .bind(dataset)
+    .bind(before)
+    .map(|row: PgRow| {
+        let swimmer = Swimmer {
+            gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+            ..Swimmer::new(row.get("id"), row.get("first_name"), row.get("last_name"))
+        };
+        let mut swimmer_time = SwimmerTime::new(
+            swimmer,
+            Meet::new(row.get::<Option<String>, _>("meet").unwrap_or_default(), row.get("course")),
+            row.get("dataset"),
+        );
+        swimmer_time.style = row.get("style");
+        swimmer_time.distance = row.get("distance");
+        swimmer_time.course = row.get("course");
+        swimmer_time.time = row.get("official_time");
+        swimmer_time.time_date = row.get("date_time");
+        swimmer_time
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch times to compare")
+}
+
+pub async fn find_all_standard_sets(conn: &PgPool, tenant: &str) -> Vec<StandardSet> {
+    sqlx::query(
+        "
+            select ss.id, ss.name, ss.qualifying_start, ss.qualifying_end, ss.tracked, count(ts.id) as num_standards
+            from standard_set ss
+                left join time_standard ts on ts.standard_set = ss.id
+            where ss.tenant = $1
+            group by ss.id, ss.name, ss.qualifying_start, ss.qualifying_end, ss.tracked
+            order by ss.name
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| StandardSet {
+        id: row.get("id"),
+        name: row.get("name"),
+        qualifying_start: row.get("qualifying_start"),
+        qualifying_end: row.get("qualifying_end"),
+        num_standards: row.get("num_standards"),
+        tracked: row.get("tracked"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch standard sets")
+}
+
+pub async fn find_standard_set(conn: &PgPool, tenant: &str, set_id: i32) -> Option<StandardSet> {
+    find_all_standard_sets(conn, tenant)
+        .await
+        .into_iter()
+        .find(|set| set.id == set_id)
+}
+
+pub async fn insert_standard_set(
+    conn: &PgPool,
+    tenant: &str,
+    set: &StandardSet,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            insert into standard_set (tenant, name, qualifying_start, qualifying_end, tracked)
+            values ($1, $2, $3, $4, $5)
+        ",
+    )
+    .bind(tenant)
+    .bind(&set.name)
+    .bind(set.qualifying_start)
+    .bind(set.qualifying_end)
+    .bind(set.tracked)
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+pub async fn delete_standard_set(
+    conn: &PgPool,
+    tenant: &str,
+    set_id: i32,
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query(
+        "
+            delete from time_standard
+            where standard_set = (select id from standard_set where tenant = $1 and id = $2)
+        ",
+    )
+    .bind(tenant)
+    .bind(set_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("delete from standard_set where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(set_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
+
+pub async fn update_standard_set_tracking(conn: &PgPool, tenant: &str, set_id: i32, tracked: bool) {
+    sqlx::query("update standard_set set tracked = $3 where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(set_id)
+        .bind(tracked)
+        .execute(conn)
+        .await
+        .expect("Failed to update the tracking of standards");
+}
+
+/// Replaces the standards of the set with the loaded ones, so a corrected file can be loaded again.
+pub async fn replace_time_standards(
+    conn: &PgPool,
+    set_id: i32,
+    standards: &[TimeStandard],
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query("delete from time_standard where standard_set = $1")
+        .bind(set_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for standard in standards {
+        sqlx::query(
+            "
+                insert into time_standard (standard_set, level, gender, style, distance, course, min_age, max_age,
+                                           standard_time)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                on conflict do nothing
+            ",
+        )
+        .bind(set_id)
+        .bind(&standard.level)
+        .bind(&standard.gender)
+        .bind(&standard.style)
+        .bind(standard.distance)
+        .bind(&standard.course)
+        .bind(standard.min_age)
+        .bind(standard.max_age)
+        .bind(standard.time)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+/// Standards of the tenant, optionally of a set, ordered by event and from the slowest to the
+/// fastest time.
+pub async fn find_time_standards(
+    conn: &PgPool,
+    tenant: &str,
+    set_id: Option<i32>,
+) -> Vec<TimeStandard> {
+    sqlx::query(
+        "
+            select ts.id, ts.standard_set, ss.name as set_name, ts.level, ts.gender, ts.style, ts.distance,
+                   ts.course, ts.min_age, ts.max_age, ts.standard_time
+            from time_standard ts
+                join standard_set ss on ss.id = ts.standard_set
+            where ss.tenant = $1 and ($2::integer is null or ss.id = $2)
+            order by ts.gender, ts.course, ts.style, ts.distance, ts.min_age nulls first, ts.standard_time desc
+        ",
+    )
+    .bind(tenant)
+    .bind(set_id)
+    .map(|row: PgRow| TimeStandard {
+        id: row.get("id"),
+        standard_set: row.get("standard_set"),
+        set_name: row.get("set_name"),
+        level: row.get("level"),
+        gender: row.get("gender"),
+        style: row.get("style"),
+        distance: row.get("distance"),
+        course: row.get("course"),
+        min_age: row.get("min_age"),
+        max_age: row.get("max_age"),
+        time: row.get("standard_time"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch time standards")
+}
+
+/// Every time of the swimmers up to the date, oldest first, to draw their progression.
+pub async fn find_swimmers_history(
+    conn: &PgPool,
+    tenant: &str,
+    swimmers: &[String],
+    until: NaiveDate,
+) -> Vec<SwimmerTime> {
+    sqlx::query(
+        "
+            select s.id, s.first_name, s.last_name, s.gender, s.birth_date,
+                   st.style, st.distance, st.course, st.official_time, st.date_time, st.dataset,
+                   m.id as meet_id, m.name as meet_name, m.start_date
+            from swimmer_time st
+                join swimmer s on s.tenant = st.tenant and s.id = st.swimmer
+                join meet m on m.id = st.meet
+            where st.tenant = $1 and st.swimmer = any($2) and st.date_time <= $3
+            order by st.swimmer, st.date_time, st.official_time
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmers)
+    .bind(until)
+    .map(|row: PgRow| {
+        let swimmer = Swimmer {
+            gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+            birth_date: row.get("birth_date"),
+            ..Swimmer::new(row.get("id"), row.get("first_name"), row.get("last_name"))
+        };
+        let meet = Meet {
+            name: row.get("meet_name"),
+            start_date: row.get("start_date"),
+            ..Meet::new(row.get("meet_id"), row.get("course"))
+        };
+        let mut swimmer_time = SwimmerTime::new(swimmer, meet, row.get("dataset"));
+        swimmer_time.style = row.get("style");
+        swimmer_time.distance = row.get("distance");
+        swimmer_time.course = row.get("course");
+        swimmer_time.time = row.get("official_time");
+        swimmer_time.time_date = row.get("date_time");
+        swimmer_time
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch the history of swimmers")
+}
+
+pub async fn find_points_tables(conn: &PgPool, tenant: &str) -> Vec<CustomPointsTable> {
+    sqlx::query(
+        "
+            select pt.id, pt.name, pt.kind, pt.active, count(pr.id) as num_rows
+            from points_table pt
+                left join points_row pr on pr.points_table = pt.id
+            where pt.tenant = $1
+            group by pt.id, pt.name, pt.kind, pt.active
+            order by pt.name
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| CustomPointsTable {
+        id: row.get("id"),
+        name: row.get("name"),
+        kind: row.get("kind"),
+        active: row.get("active"),
+        num_rows: row.get("num_rows"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch points tables")
+}
+
+pub async fn find_points_rows(conn: &PgPool, table_id: i32) -> Vec<PointsRow> {
+    sqlx::query(
+        "
+            select gender, style, distance, course, min_age, max_age, row_time, points
+            from points_row
+            where points_table = $1
+            order by gender, course, style, distance, min_age nulls first, row_time desc
+        ",
+    )
+    .bind(table_id)
+    .map(|row: PgRow| PointsRow {
+        gender: row.get("gender"),
+        style: row.get("style"),
+        distance: row.get("distance"),
+        course: row.get("course"),
+        min_age: row.get("min_age"),
+        max_age: row.get("max_age"),
+        time: row.get("row_time"),
+        points: row.get("points"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch points rows")
+}
+
+/// Saves a points table with its rows. Loading a table with an existing name replaces its rows.
+pub async fn save_points_table(
+    conn: &PgPool,
+    tenant: &str,
+    name: &str,
+    kind: &str,
+    rows: &[PointsRow],
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let table_id: i32 = sqlx::query(
+        "
+            insert into points_table (tenant, name, kind)
+            values ($1, $2, $3)
+            on conflict (tenant, name) do update set kind = excluded.kind
+            returning id
+        ",
+    )
+    .bind(tenant)
+    .bind(name)
+    .bind(kind)
+    .map(|row: PgRow| row.get("id"))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("delete from points_row where points_table = $1")
+        .bind(table_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for row in rows {
+        sqlx::query(
+            "
+                insert into points_row (points_table, gender, style, distance, course, min_age, max_age, row_time,
+                                        points)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ",
+        )
+        .bind(table_id)
+        .bind(&row.gender)
+        .bind(&row.style)
+        .bind(row.distance)
+        .bind(&row.course)
+        .bind(row.min_age)
+        .bind(row.max_age)
+        .bind(row.time)
+        .bind(row.points)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+/// Makes the table the one scoring the times of the tenant. Without a table, World Aquatics
+/// points are used.
+pub async fn activate_points_table(conn: &PgPool, tenant: &str, table_id: Option<i32>) {
+    sqlx::query(
+        "update points_table set active = ($2::integer is not null and id = $2) where tenant = $1",
+    )
+    .bind(tenant)
+    .bind(table_id)
+    .execute(conn)
+    .await
+    .expect("Failed to activate points table");
+}
+
+pub async fn delete_points_table(
+    conn: &PgPool,
+    tenant: &str,
+    table_id: i32,
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query(
+        "
+            delete from points_row
+            where points_table = (select id from points_table where tenant = $1 and id = $2)
+        ",
+    )
+    .bind(tenant)
+    .bind(table_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("delete from points_table where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(table_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
+
+pub async fn find_age_rule(conn: &PgPool, tenant: &str) -> Option<String> {
+    sqlx::query("select rule from age_rule where tenant = $1")
+        .bind(tenant)
+        .map(|row: PgRow| row.get("rule"))
+        .fetch_optional(conn)
+        .await
+        .expect("Failed to fetch age rule")
+}
+
+pub async fn save_age_rule(conn: &PgPool, tenant: &str, rule: &str) {
+    sqlx::query(
+        "
+            insert into age_rule (tenant, rule)
+            values ($1, $2)
+            on conflict (tenant) do update set rule = excluded.rule
+        ",
+    )
+    .bind(tenant)
+    .bind(rule)
+    .execute(conn)
+    .await
+    .expect("Failed to save age rule");
+}
+
+/// Age groups of the tenant, from the youngest to the oldest.
+pub async fn find_age_groups(conn: &PgPool, tenant: &str) -> Vec<AgeGroup> {
+    sqlx::query(
+        "
+            select id, name, min_age, max_age
+            from age_group
+            where tenant = $1
+            order by min_age nulls first, max_age nulls last
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| AgeGroup {
+        id: row.get("id"),
+        name: row.get("name"),
+        min_age: row.get("min_age"),
+        max_age: row.get("max_age"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch age groups")
+}
+
+pub async fn insert_age_group(
+    conn: &PgPool,
+    tenant: &str,
+    age_group: &AgeGroup,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            insert into age_group (tenant, name, min_age, max_age)
+            values ($1, $2, $3, $4)
+        ",
+    )
+    .bind(tenant)
+    .bind(&age_group.name)
+    .bind(age_group.min_age)
+    .bind(age_group.max_age)
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+pub async fn delete_age_group(conn: &PgPool, tenant: &str, age_group_id: i32) {
+    sqlx::query("delete from age_group where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(age_group_id)
+        .execute(conn)
+        .await
+        .expect("Failed to delete age group");
+}
+
+/// The fastest time of each swimmer in each event, optionally in a course.
+pub async fn find_swimmers_best_times(
+    conn: &PgPool,
+    tenant: &str,
+    swimmers: &[String],
+    course: &str,
+) -> Vec<SwimmerTime> {
+    sqlx::query(
+        "
+            select distinct on (s.id, st.course, st.style, st.distance)
+                   s.id, s.first_name, s.last_name, s.gender, s.birth_date,
+                   st.style, st.distance, st.course, st.official_time, st.date_time, st.dataset,
+                   m.id as meet_id, m.name as meet_name, m.start_date
+            from swimmer_time st
+                join swimmer s on s.tenant = st.tenant and s.id = st.swimmer
+                join meet m on m.id = st.meet
+            where st.tenant = $1 and st.swimmer = any($2) and ($3 = '' or st.course = $3)
+            order by s.id, st.course, st.style, st.distance, st.official_time, st.date_time
+        ",
+    )
+    .bind(tenant)
+    .bind(swimmers)
+    .bind(course)
+    .map(|row: PgRow| {
+        let swimmer = Swimmer {
+            gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+            birth_date: row.get("birth_date"),
+            ..Swimmer::new(row.get("id"), row.get("first_name"), row.get("last_name"))
+        };
+        let meet = Meet {
+            name: row.get("meet_name"),
+            start_date: row.get("start_date"),
+            ..Meet::new(row.get("meet_id"), row.get("course"))
+        };
+        let mut swimmer_time = SwimmerTime::new(swimmer, meet, row.get("dataset"));
+        swimmer_time.style = row.get("style");
+        swimmer_time.distance = row.get("distance");
+        swimmer_time.course = row.get("course");
+        swimmer_time.time = row.get("official_time");
+        swimmer_time.time_date = row.get("date_time");
+        swimmer_time
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch best times of swimmers")
+}
+
+/// Every club record of the tenant, from the oldest.
+pub async fn find_club_records(conn: &PgPool, tenant: &str) -> Vec<ClubRecord> {
+    sqlx::query(
+        "
+            select id, age_group, gender, style, distance, course, record_time, swimmer, swimmer_name,
+                   record_date, meet, meet_name, source
+            from club_record
+            where tenant = $1
+            order by record_date, record_time
+        ",
+    )
+    .bind(tenant)
+    .map(|row: PgRow| ClubRecord {
+        id: row.get("id"),
+        age_group: row.get("age_group"),
+        gender: row.get("gender"),
+        style: row.get("style"),
+        distance: row.get("distance"),
+        course: row.get("course"),
+        time: row.get("record_time"),
+        swimmer: row.get::<Option<String>, _>("swimmer").unwrap_or_default(),
+        swimmer_name: row.get("swimmer_name"),
+        record_date: row.get("record_date"),
+        meet: row.get::<Option<String>, _>("meet").unwrap_or_default(),
+        meet_name: row.get("meet_name"),
+        source: row.get("source"),
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch club records")
+}
+
+pub async fn insert_club_records(
+    conn: &PgPool,
+    tenant: &str,
+    records: &[ClubRecord],
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    for record in records {
+        insert_club_record(&mut tx, tenant, record).await?;
+    }
+
+    tx.commit().await
+}
+
+/// Replaces the records seeded from a file before, keeping the records detected in meet results.
+pub async fn replace_seeded_club_records(
+    conn: &PgPool,
+    tenant: &str,
+    records: &[ClubRecord],
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    sqlx::query("delete from club_record where tenant = $1 and source = 'SEED'")
+        .bind(tenant)
+        .execute(&mut *tx)
+        .await?;
+
+    for record in records {
+        insert_club_record(&mut tx, tenant, record).await?;
+    }
+
+    tx.commit().await
+}
+
+async fn insert_club_record(
+    conn: &mut PgConnection,
+    tenant: &str,
+    record: &ClubRecord,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+            insert into club_record (tenant, age_group, gender, style, distance, course, record_time, swimmer,
+                                     swimmer_name, record_date, meet, meet_name, source)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ",
+    )
+    .bind(tenant)
+    .bind(&record.age_group)
+    .bind(&record.gender)
+    .bind(&record.style)
+    .bind(record.distance)
+    .bind(&record.course)
+    .bind(record.time)
+    .bind(Some(&record.swimmer).filter(|v| !v.is_empty()))
+    .bind(&record.swimmer_name)
+    .bind(record.record_date)
+    .bind(Some(&record.meet).filter(|v| !v.is_empty()))
+    .bind(&record.meet_name)
+    .bind(&record.source)
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
+
+/// Removes the records detected in the results of the meet, to detect them again.
+pub async fn delete_meet_club_records(conn: &PgPool, tenant: &str, meet_id: &str) {
+    sqlx::query("delete from club_record where tenant = $1 and meet = $2 and source = 'IMPORT'")
+        .bind(tenant)
+        .bind(meet_id)
+        .execute(conn)
+        .await
+        .expect("Failed to delete club records of the meet");
+}
+
+pub async fn delete_club_record(conn: &PgPool, tenant: &str, record_id: i32) {
+    sqlx::query("delete from club_record where tenant = $1 and id = $2")
+        .bind(tenant)
+        .bind(record_id)
+        .execute(conn)
+        .await
+        .expect("Failed to delete club record");
+}
+
+/// Results of the meet swum by members of the club at the time.
+pub async fn find_club_results(
+    conn: &PgPool,
+    tenant: &str,
+    meet: &Meet,
+    club: &str,
+) -> Vec<SwimmerTime> {
+    sqlx::query(
+        "
+            select s.id, s.first_name, s.last_name, s.gender, s.birth_date,
+                   st.style, st.distance, st.course, st.official_time, st.date_time, st.dataset
+            from swimmer_time st
+                join swimmer s on s.tenant = st.tenant and s.id = st.swimmer
+            where st.tenant = $1 and st.meet = $2 and st.dataset = 'MEET_RESULTS'
+                and exists (select 1 from swimmer_club sc
+                            where sc.tenant = s.tenant and sc.swimmer = s.id and sc.club = $3
+                                and sc.start_date <= st.date_time
+                                and (sc.end_date is null or sc.end_date >= st.date_time))
+            order by st.date_time, st.official_time
+        ",
+    )
+    .bind(tenant)
+    .bind(&meet.id)
+    .bind(club)
+    .map(|row: PgRow| {
+        let swimmer = Swimmer {
+            gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+            birth_date: row.get("birth_date"),
+            ..Swimmer::new(row.get("id"), row.get("first_name"), row.get("last_name"))
+        };
+        let mut swimmer_time = SwimmerTime::new(swimmer, meet.clone(), row.get("dataset"));
+        swimmer_time.style = row.get("style");
+        swimmer_time.distance = row.get("distance");
+        swimmer_time.course = row.get("course");
+        swimmer_time.time = row.get("official_time");
+        swimmer_time.time_date = row.get("date_time");
+        swimmer_time
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch club results of the meet")
+}
+
+/// Meet results swum by members of the club at the time, optionally within a season.
+pub async fn find_club_swims(
+    conn: &PgPool,
+    tenant: &str,
+    club: &str,
+    season: Option<&Season>,
+) -> Vec<SwimmerTime> {
+    sqlx::query(
+        "
+            select s.id, s.first_name, s.last_name, s.gender, s.birth_date,
+                   st.style, st.distance, st.course, st.official_time, st.date_time, st.dataset,
+                   m.id as meet_id, m.name as meet_name, m.start_date
+            from swimmer_time st
+                join swimmer s on s.tenant = st.tenant and s.id = st.swimmer
+                join meet m on m.id = st.meet
+            where st.tenant = $1 and st.dataset = 'MEET_RESULTS'
+                and ($3::date is null or st.date_time between $3 and $4)
+                and exists (select 1 from swimmer_club sc
+                            where sc.tenant = s.tenant and sc.swimmer = s.id and sc.club = $2
+                                and sc.start_date <= st.date_time
+                                and (sc.end_date is null or sc.end_date >= st.date_time))
+            order by st.official_time, st.date_time
+        ",
+    )
+    .bind(tenant)
+    .bind(club)
+    .bind(season.map(|s| s.start_date))
+    .bind(season.map(|s| s.end_date))
+    .map(|row: PgRow| {
+        let swimmer = Swimmer {
+            gender: row.get::<Option<String>, _>("gender").unwrap_or_default(),
+            birth_date: row.get("birth_date"),
+            ..Swimmer::new(row.get("id"), row.get("first_name"), row.get("last_name"))
+        };
+        let meet = Meet {
+            name: row.get("meet_name"),
+            start_date: row.get("start_date"),
+            ..Meet::new(row.get("meet_id"), row.get("course"))
+        };
+        let mut swimmer_time = SwimmerTime::new(swimmer, meet, row.get("dataset"));
+        swimmer_time.style = row.get("style");
+        swimmer_time.distance = row.get("distance");
+        swimmer_time.course = row.get("course");
+        swimmer_time.time = row.get("official_time");
+        swimmer_time.time_date = row.get("date_time");
+        swimmer_time
+    })
+    .fetch_all(conn)
+    .await
+    .expect("Failed to fetch swims of the club")
+}
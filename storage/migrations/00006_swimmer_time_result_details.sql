@@ -0,0 +1,9 @@
+alter table swimmer_time add if not exists round varchar(20);
+alter table swimmer_time add if not exists place integer;
+alter table swimmer_time add if not exists heat integer;
+alter table swimmer_time add if not exists lane integer;
+alter table swimmer_time add if not exists reaction_time integer;
+
+drop index if exists udx_swimmer_time;
+
+create unique index if not exists udx_swimmer_time on swimmer_time (swimmer, style, distance, course, official_time, date_time, dataset, coalesce(round, ''));
@@ -0,0 +1,17 @@
+create table if not exists meet_session (
+    id           serial      primary key,
+    meet         varchar(32) not null references meet (id),
+    session_date date        not null,
+    period       varchar(10) not null
+);
+
+create index if not exists idx_meet_session_meet on meet_session (meet);
+
+create table if not exists meet_event (
+    id       serial      primary key,
+    session  integer     not null references meet_session (id),
+    gender   varchar(10),
+    style    varchar(20) not null,
+    distance integer     not null,
+    round    varchar(20)
+);
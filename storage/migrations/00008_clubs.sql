@@ -0,0 +1,17 @@
+create table if not exists club (
+    code varchar(10)  primary key,
+    name varchar(100) not null
+);
+
+create table if not exists swimmer_club (
+    id         serial      primary key,
+    swimmer    varchar(32) not null references swimmer (id),
+    club       varchar(10) not null references club (code),
+    start_date date        not null,
+    end_date   date
+);
+
+create unique index if not exists udx_swimmer_club on swimmer_club (swimmer, club, start_date);
+
+-- Swimmers from other clubs are only known by their results, which don't have a date of birth.
+alter table swimmer alter column birth_date drop not null;
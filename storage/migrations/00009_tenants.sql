@@ -0,0 +1,43 @@
+create table if not exists tenant (
+    id   varchar(32)  primary key,
+    name varchar(100) not null,
+    host varchar(100) unique,
+    club varchar(10)  references club (code)
+);
+
+insert into tenant (id, name) values ('default', 'Default') on conflict do nothing;
+
+-- Meets are shared by all tenants, who follow the ones they take part in.
+create table if not exists tenant_meet (
+    tenant varchar(32) not null references tenant (id),
+    meet   varchar(32) not null references meet (id),
+    primary key (tenant, meet)
+);
+
+insert into tenant_meet (tenant, meet) select 'default', id from meet on conflict do nothing;
+
+alter table swimmer add if not exists tenant varchar(32) not null default 'default' references tenant (id);
+alter table swimmer_time add if not exists tenant varchar(32) not null default 'default' references tenant (id);
+alter table swimmer_club add if not exists tenant varchar(32) not null default 'default' references tenant (id);
+alter table import_history add if not exists tenant varchar(32) not null default 'default' references tenant (id);
+
+alter table swimmer alter column tenant drop default;
+alter table swimmer_time alter column tenant drop default;
+alter table swimmer_club alter column tenant drop default;
+alter table import_history alter column tenant drop default;
+
+-- Swimmers are identified within their tenant, so several clubs can follow the same swimmer.
+alter table swimmer_time drop constraint if exists swimmer_time_swimmer_fkey;
+alter table swimmer_club drop constraint if exists swimmer_club_swimmer_fkey;
+alter table swimmer drop constraint if exists swimmer_pkey;
+alter table swimmer add primary key (tenant, id);
+alter table swimmer_time add foreign key (tenant, swimmer) references swimmer (tenant, id);
+alter table swimmer_club add foreign key (tenant, swimmer) references swimmer (tenant, id);
+
+drop index if exists udx_swimmer_time;
+create unique index if not exists udx_swimmer_time on swimmer_time (tenant, swimmer, style, distance, course, official_time, date_time, dataset, coalesce(round, ''));
+
+drop index if exists udx_swimmer_club;
+create unique index if not exists udx_swimmer_club on swimmer_club (tenant, swimmer, club, start_date);
+
+create index if not exists idx_import_history_tenant on import_history (tenant, meet);
@@ -0,0 +1,26 @@
+create table if not exists swimmer_alias (
+    id         serial      primary key,
+    tenant     varchar(32) not null,
+    swimmer    varchar(32) not null,
+    alias_id   varchar(32),
+    first_name varchar(50),
+    last_name  varchar(50),
+    foreign key (tenant, swimmer) references swimmer (tenant, id)
+);
+
+create unique index if not exists udx_swimmer_alias_id on swimmer_alias (tenant, alias_id);
+create index if not exists idx_swimmer_alias_name on swimmer_alias (tenant, first_name, last_name);
+
+create table if not exists swimmer_merge (
+    id                serial       primary key,
+    tenant            varchar(32)  not null references tenant (id),
+    survivor          varchar(32)  not null,
+    merged            varchar(32)  not null,
+    merged_first_name varchar(50)  not null,
+    merged_last_name  varchar(50)  not null,
+    merged_gender     varchar(10)  not null,
+    merged_birth_date date,
+    num_times         integer      not null,
+    num_discarded     integer      not null,
+    merge_time        timestamp    not null default current_timestamp
+);
@@ -0,0 +1,33 @@
+create table if not exists name_review (
+    id          serial       primary key,
+    tenant      varchar(32)  not null references tenant (id),
+    meet        varchar(32)  not null references meet (id),
+    full_name   varchar(100) not null,
+    club        varchar(10),
+    gender      varchar(10),
+    status      varchar(10)  not null default 'PENDING',
+    swimmer     varchar(32),
+    created_at  timestamp    not null default current_timestamp,
+    resolved_at timestamp
+);
+
+create unique index if not exists udx_name_review on name_review (tenant, meet, full_name, coalesce(club, ''));
+
+-- Times of swimmers under review wait here until a coach links or creates the swimmer.
+create table if not exists name_review_time (
+    id            serial      primary key,
+    review        integer     not null references name_review (id),
+    style         varchar(20) not null,
+    distance      integer     not null,
+    course        varchar(10) not null,
+    official_time integer     not null,
+    date_time     date,
+    dataset       varchar(20) not null,
+    round         varchar(20),
+    place         integer,
+    heat          integer,
+    lane          integer,
+    reaction_time integer
+);
+
+create unique index if not exists udx_name_review_time on name_review_time (review, style, distance, course, official_time, date_time, dataset, coalesce(round, ''));
@@ -0,0 +1,2 @@
+-- Swimmers who left the club are kept with their times, but hidden from the list of swimmers.
+alter table swimmer add column if not exists active boolean not null default true;
@@ -0,0 +1,34 @@
+create table if not exists coach (
+    id     serial       primary key,
+    tenant varchar(32)  not null references tenant (id),
+    name   varchar(100) not null,
+    email  varchar(100)
+);
+
+create unique index if not exists udx_coach on coach (tenant, name);
+
+create table if not exists training_group (
+    id     serial      primary key,
+    tenant varchar(32) not null references tenant (id),
+    name   varchar(50) not null
+);
+
+create unique index if not exists udx_training_group on training_group (tenant, name);
+
+create table if not exists training_group_coach (
+    training_group integer not null references training_group (id),
+    coach          integer not null references coach (id),
+    primary key (training_group, coach)
+);
+
+create table if not exists swimmer_group (
+    id             serial      primary key,
+    tenant         varchar(32) not null,
+    swimmer        varchar(32) not null,
+    training_group integer     not null references training_group (id),
+    start_date     date        not null,
+    end_date       date,
+    foreign key (tenant, swimmer) references swimmer (tenant, id)
+);
+
+create unique index if not exists udx_swimmer_group on swimmer_group (tenant, swimmer, training_group, start_date);
@@ -0,0 +1,12 @@
+-- Seasons usually run from September to August, swimming short course until the long course
+-- phase starts.
+create table if not exists season (
+    id                serial      primary key,
+    tenant            varchar(32) not null references tenant (id),
+    name              varchar(50) not null,
+    start_date        date        not null,
+    end_date          date        not null,
+    long_course_start date
+);
+
+create unique index if not exists udx_season on season (tenant, name);
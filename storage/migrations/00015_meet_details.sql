@@ -0,0 +1,14 @@
+alter table meet add column if not exists location  varchar(100);
+alter table meet add column if not exists pool      varchar(100);
+alter table meet add column if not exists host_club varchar(10);
+alter table meet add column if not exists sanction  varchar(50);
+alter table meet add column if not exists meet_type varchar(20);
+
+-- Meets created before the course was captured get the course most of their results were swum in.
+update meet m
+set course = (select st.course from swimmer_time st
+              where st.meet = m.id and st.dataset = 'MEET_RESULTS'
+              group by st.course
+              order by count(*) desc
+              limit 1)
+where m.course is null;
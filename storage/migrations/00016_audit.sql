@@ -0,0 +1,18 @@
+-- Audit entries outlive what they describe, so meets and swimmers are not foreign keys.
+create table if not exists audit (
+    id         serial       primary key,
+    tenant     varchar(32)  not null references tenant (id),
+    coach      integer      references coach (id),
+    actor      varchar(100) not null,
+    action     varchar(20)  not null,
+    entity     varchar(20)  not null,
+    entity_id  varchar(50)  not null,
+    meet       varchar(32),
+    swimmer    varchar(32),
+    before     jsonb,
+    after      jsonb,
+    created_at timestamp    not null default current_timestamp
+);
+
+create index if not exists idx_audit_meet on audit (tenant, meet);
+create index if not exists idx_audit_swimmer on audit (tenant, swimmer);
@@ -0,0 +1,14 @@
+-- Factors customized by the tenant. Events without a row use the defaults of the application.
+create table if not exists course_conversion (
+    id            serial           primary key,
+    tenant        varchar(32)      not null references tenant (id),
+    gender        varchar(10)      not null,
+    style         varchar(20)      not null,
+    from_course   varchar(10)      not null,
+    from_distance integer          not null,
+    to_course     varchar(10)      not null,
+    to_distance   integer          not null,
+    factor        double precision not null
+);
+
+create unique index if not exists udx_course_conversion on course_conversion (tenant, gender, style, from_course, from_distance, to_course);
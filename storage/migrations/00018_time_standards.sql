@@ -0,0 +1,25 @@
+-- Standards, like provincial or national cuts, grouped in sets with the window in which times qualify.
+create table if not exists standard_set (
+    id               serial       primary key,
+    tenant           varchar(32)  not null references tenant (id),
+    name             varchar(100) not null,
+    qualifying_start date,
+    qualifying_end   date
+);
+
+create unique index if not exists udx_standard_set on standard_set (tenant, name);
+
+create table if not exists time_standard (
+    id            serial      primary key,
+    standard_set  integer     not null references standard_set (id),
+    level         varchar(20) not null default '',
+    gender        varchar(10) not null,
+    style         varchar(20) not null,
+    distance      integer     not null,
+    course        varchar(10) not null,
+    min_age       integer,
+    max_age       integer,
+    standard_time integer     not null
+);
+
+create unique index if not exists udx_time_standard on time_standard (standard_set, level, gender, style, distance, course, coalesce(min_age, 0), coalesce(max_age, 0));
@@ -0,0 +1,26 @@
+-- Points tables loaded by the tenant. World Aquatics points are built into the application and
+-- used while no table is active.
+create table if not exists points_table (
+    id     serial       primary key,
+    tenant varchar(32)  not null references tenant (id),
+    name   varchar(100) not null,
+    kind   varchar(20)  not null,
+    active boolean      not null default false
+);
+
+create unique index if not exists udx_points_table on points_table (tenant, name);
+
+create table if not exists points_row (
+    id           serial      primary key,
+    points_table integer     not null references points_table (id),
+    gender       varchar(10) not null,
+    style        varchar(20) not null,
+    distance     integer     not null,
+    course       varchar(10) not null,
+    min_age      integer,
+    max_age      integer,
+    row_time     integer     not null,
+    points       integer
+);
+
+create index if not exists idx_points_row on points_row (points_table);
@@ -0,0 +1,16 @@
+-- How the tenant's federation determines the age of swimmers, and the age groups it competes in.
+-- Tenants without a rule or groups use the defaults of the application.
+create table if not exists age_rule (
+    tenant varchar(32) primary key references tenant (id),
+    rule   varchar(20) not null
+);
+
+create table if not exists age_group (
+    id      serial      primary key,
+    tenant  varchar(32) not null references tenant (id),
+    name    varchar(50) not null,
+    min_age integer,
+    max_age integer
+);
+
+create unique index if not exists udx_age_group on age_group (tenant, name);
@@ -0,0 +1,2 @@
+-- Sets whose qualifications are tracked and announced after meets.
+alter table standard_set add column if not exists tracked boolean not null default false;
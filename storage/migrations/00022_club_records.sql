@@ -0,0 +1,20 @@
+-- Every club record ever set, by age group, gender, course and event. The fastest of a category
+-- is the current record, and the others its history. Seeded records may lack the swimmer and meet.
+create table if not exists club_record (
+    id           serial       primary key,
+    tenant       varchar(32)  not null references tenant (id),
+    age_group    varchar(50)  not null,
+    gender       varchar(10)  not null,
+    style        varchar(20)  not null,
+    distance     integer      not null,
+    course       varchar(10)  not null,
+    record_time  integer      not null,
+    swimmer      varchar(32),
+    swimmer_name varchar(100) not null,
+    record_date  date         not null,
+    meet         varchar(32),
+    meet_name    varchar(200) not null default '',
+    source       varchar(10)  not null
+);
+
+create index if not exists idx_club_record on club_record (tenant, meet);
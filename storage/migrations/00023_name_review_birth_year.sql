@@ -0,0 +1,2 @@
+-- Results may tell the age or year of birth, which helps matching names under review.
+alter table name_review add column if not exists birth_year integer;
@@ -0,0 +1,3 @@
+-- Swimmers imported before genders were converted keep the values of the file, like "F" or "M".
+update swimmer set gender = 'FEMALE' where upper(trim(gender)) in ('F', 'W', 'GIRLS', 'WOMEN');
+update swimmer set gender = 'MALE' where upper(trim(gender)) in ('M', 'BOYS', 'MEN');